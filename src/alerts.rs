@@ -0,0 +1,366 @@
+use crate::collector::SystemStats;
+use chrono::Utc;
+use serde::Serialize;
+
+// 内存 / IO 压力（PSI avg10，%）告警阈值：
+// some 为至少一个任务停顿的时间占比，full 为所有任务同时停顿（整机实际停摆）的时间占比
+const PRESSURE_SOME_WARNING_PERCENT: f64 = 20.0;
+const PRESSURE_FULL_WARNING_PERCENT: f64 = 5.0;
+const PRESSURE_FULL_CRITICAL_PERCENT: f64 = 20.0;
+// OOM 事件发生后保持告警的时间（秒）
+const OOM_ALERT_WINDOW_SECS: i64 = 600;
+// inode 使用率告警阈值（%）
+const INODE_WARNING_PERCENT: f64 = 90.0;
+const INODE_CRITICAL_PERCENT: f64 = 95.0;
+// 预计在该时间内写满则告警（小时）
+const DISK_FULL_ALERT_HOURS: f64 = 24.0;
+// NVMe 寿命消耗告警阈值（%）
+const NVME_WEAR_WARNING_PERCENT: u64 = 90;
+// 探测连续失败次数达到该值视为不可达
+const PROBE_FAILURE_COUNT: u32 = 3;
+// 探测历史窗口内的丢包率告警阈值（%）
+const PROBE_LOSS_WARNING_PERCENT: f64 = 20.0;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Critical,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Alert {
+    // 告警类型，前端和外部脚本按此区分，如 oom_kill
+    pub kind: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// 根据一次采样结果生成当前生效的告警
+pub fn evaluate(stats: &SystemStats) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+    check_oom(stats, &mut alerts);
+    check_pressure(stats, &mut alerts);
+    check_filesystems(stats, &mut alerts);
+    check_storage_health(stats, &mut alerts);
+    check_smart(stats, &mut alerts);
+    check_probes(stats, &mut alerts);
+    check_gpus(stats, &mut alerts);
+    check_thermal(stats, &mut alerts);
+    alerts
+}
+
+/// 最近发生过 OOM：全局 OOM 为严重，cgroup 内 OOM（容器/服务触发内存上限）为警告
+fn check_oom(stats: &SystemStats, alerts: &mut Vec<Alert>) {
+    let since = Utc::now().timestamp() - OOM_ALERT_WINDOW_SECS;
+
+    for event in stats
+        .oom
+        .recent_events
+        .iter()
+        .filter(|e| e.timestamp >= since)
+    {
+        let victim = match (&event.process_name, event.pid) {
+            (Some(name), Some(pid)) => format!("{} (PID {})", name, pid),
+            _ => "未知进程".to_string(),
+        };
+        let (severity, scope) = match &event.cgroup {
+            Some(cgroup) => (Severity::Warning, format!("cgroup {}", cgroup)),
+            None => (Severity::Critical, "系统".to_string()),
+        };
+
+        alerts.push(Alert {
+            kind: "oom_kill".to_string(),
+            severity,
+            message: format!("{} 发生 OOM，被终止: {}", scope, victim),
+        });
+    }
+}
+
+/// 内存 / IO 压力过高：任务频繁因缺页回收或等待磁盘而停顿，
+/// 比内存使用率、负载更直接地反映“机器变卡”
+fn check_pressure(stats: &SystemStats, alerts: &mut Vec<Alert>) {
+    let resources = [
+        ("memory_pressure", "内存", &stats.pressure.memory),
+        ("io_pressure", "IO", &stats.pressure.io),
+    ];
+    for (kind, resource, pressure) in resources {
+        let Some(pressure) = pressure else {
+            continue;
+        };
+        let some = pressure.some.avg10;
+        let full = pressure.full.as_ref().map_or(0.0, |l| l.avg10);
+
+        let severity = if full >= PRESSURE_FULL_CRITICAL_PERCENT {
+            Severity::Critical
+        } else if full >= PRESSURE_FULL_WARNING_PERCENT || some >= PRESSURE_SOME_WARNING_PERCENT {
+            Severity::Warning
+        } else {
+            continue;
+        };
+        alerts.push(Alert {
+            kind: kind.to_string(),
+            severity,
+            message: format!(
+                "{}压力过高：some avg10 {:.1}%，full avg10 {:.1}%",
+                resource, some, full
+            ),
+        });
+    }
+}
+
+/// inode 即将耗尽（磁盘有空间也无法创建文件）、按当前增长速度即将写满，
+/// 以及文件系统在运行期间变为只读（ext4 出错后会自动 remount 为只读）
+fn check_filesystems(stats: &SystemStats, alerts: &mut Vec<Alert>) {
+    for disk in &stats.disks {
+        if let Some(percent) = disk.inodes_usage_percent {
+            let severity = if percent >= INODE_CRITICAL_PERCENT {
+                Some(Severity::Critical)
+            } else if percent >= INODE_WARNING_PERCENT {
+                Some(Severity::Warning)
+            } else {
+                None
+            };
+            if let Some(severity) = severity {
+                alerts.push(Alert {
+                    kind: "inode_usage".to_string(),
+                    severity,
+                    message: format!("{} inode 使用率 {:.1}%", disk.mount_point, percent),
+                });
+            }
+        }
+
+        if let Some(hours) = disk
+            .forecast
+            .as_ref()
+            .and_then(|f| f.hours_to_full)
+            .filter(|h| *h <= DISK_FULL_ALERT_HOURS)
+        {
+            alerts.push(Alert {
+                kind: "disk_full_forecast".to_string(),
+                severity: Severity::Critical,
+                message: format!("{} 预计 {:.1} 小时内写满", disk.mount_point, hours),
+            });
+        }
+
+        if disk.remounted_read_only {
+            alerts.push(Alert {
+                kind: "read_only".to_string(),
+                severity: Severity::Critical,
+                message: format!("{} 已由读写变为只读", disk.mount_point),
+            });
+        }
+    }
+}
+
+/// RAID 阵列降级或正在重建、ZFS 存储池状态异常、device-mapper 设备被挂起
+fn check_storage_health(stats: &SystemStats, alerts: &mut Vec<Alert>) {
+    let health = &stats.storage_health;
+
+    for array in &health.md_arrays {
+        if array.degraded {
+            let failed: Vec<&str> = array
+                .members
+                .iter()
+                .filter(|m| m.faulty)
+                .map(|m| m.device.as_str())
+                .collect();
+            alerts.push(Alert {
+                kind: "raid_degraded".to_string(),
+                severity: Severity::Critical,
+                message: format!(
+                    "RAID {} 已降级 {}{}",
+                    array.name,
+                    array.member_status.as_deref().unwrap_or(""),
+                    if failed.is_empty() {
+                        String::new()
+                    } else {
+                        format!("，故障成员: {}", failed.join(", "))
+                    }
+                ),
+            });
+        }
+        // check / repair 是定期的数据校验（多数发行版每月自动执行），不代表阵列有问题
+        if let Some(sync) = array
+            .sync
+            .as_ref()
+            .filter(|s| matches!(s.action.as_str(), "recovery" | "resync" | "reshape"))
+        {
+            alerts.push(Alert {
+                kind: "raid_sync".to_string(),
+                severity: Severity::Warning,
+                message: format!(
+                    "RAID {} 正在 {}（{:.1}%）",
+                    array.name, sync.action, sync.progress_percent
+                ),
+            });
+        }
+    }
+
+    if let Some(zfs) = &health.zfs {
+        for pool in zfs.pools.iter().filter(|p| p.state != "ONLINE") {
+            alerts.push(Alert {
+                kind: "zfs_pool".to_string(),
+                severity: Severity::Critical,
+                message: format!("ZFS 存储池 {} 状态: {}", pool.name, pool.state),
+            });
+        }
+    }
+
+    for device in health.device_mapper.iter().filter(|d| d.suspended) {
+        alerts.push(Alert {
+            kind: "dm_suspended".to_string(),
+            severity: Severity::Warning,
+            message: format!("device-mapper 设备 {} 已挂起", device.name),
+        });
+    }
+}
+
+/// SMART 自检未通过、出现重映射/待映射扇区、NVMe 寿命即将耗尽
+/// 同一块磁盘上的多个分区只告警一次
+fn check_smart(stats: &SystemStats, alerts: &mut Vec<Alert>) {
+    let mut seen = std::collections::HashSet::new();
+
+    for smart in stats.disks.iter().filter_map(|d| d.smart.as_ref()) {
+        if !seen.insert(smart.device.as_str()) {
+            continue;
+        }
+        let name = match &smart.model {
+            Some(model) => format!("{} ({})", smart.device, model),
+            None => smart.device.clone(),
+        };
+
+        if smart.health_passed == Some(false) {
+            alerts.push(Alert {
+                kind: "smart_failed".to_string(),
+                severity: Severity::Critical,
+                message: format!("磁盘 {} SMART 自检未通过", name),
+            });
+        }
+
+        let reallocated = smart.reallocated_sectors.unwrap_or(0);
+        let pending = smart.pending_sectors.unwrap_or(0);
+        if reallocated + pending > 0 {
+            alerts.push(Alert {
+                kind: "smart_sectors".to_string(),
+                severity: Severity::Warning,
+                message: format!(
+                    "磁盘 {} 已重映射扇区 {}，待映射扇区 {}",
+                    name, reallocated, pending
+                ),
+            });
+        }
+
+        if let Some(used) = smart
+            .nvme_percentage_used
+            .filter(|u| *u >= NVME_WEAR_WARNING_PERCENT)
+        {
+            alerts.push(Alert {
+                kind: "nvme_wear".to_string(),
+                severity: Severity::Warning,
+                message: format!("NVMe {} 寿命已消耗 {}%", name, used),
+            });
+        }
+    }
+}
+
+/// 探测目标连续失败视为不可达；偶发失败导致丢包率偏高时为警告
+fn check_probes(stats: &SystemStats, alerts: &mut Vec<Alert>) {
+    for probe in &stats.probes {
+        if probe.consecutive_failures >= PROBE_FAILURE_COUNT {
+            let error = probe
+                .last
+                .as_ref()
+                .and_then(|s| s.error.as_deref())
+                .unwrap_or("未知错误");
+            alerts.push(Alert {
+                kind: "probe_down".to_string(),
+                severity: Severity::Critical,
+                message: format!(
+                    "{} ({}) 连续 {} 次探测失败: {}",
+                    probe.name, probe.target, probe.consecutive_failures, error
+                ),
+            });
+        } else if probe.loss_percent >= PROBE_LOSS_WARNING_PERCENT {
+            alerts.push(Alert {
+                kind: "probe_loss".to_string(),
+                severity: Severity::Warning,
+                message: format!(
+                    "{} ({}) 最近失败率 {:.0}%",
+                    probe.name, probe.target, probe.loss_percent
+                ),
+            });
+        }
+    }
+}
+
+/// GPU 出现不可纠正的 ECC 错误（需要重置显卡或排查硬件），
+/// 以及因过热或电源制动被硬件降频（训练任务会明显变慢）
+fn check_gpus(stats: &SystemStats, alerts: &mut Vec<Alert>) {
+    for gpu in &stats.gpus {
+        let name = format!("GPU #{} {}", gpu.index, gpu.name);
+
+        let uncorrected = gpu
+            .ecc_errors
+            .as_ref()
+            .and_then(|ecc| ecc.uncorrected_volatile)
+            .unwrap_or(0);
+        if uncorrected > 0 {
+            alerts.push(Alert {
+                kind: "gpu_ecc".to_string(),
+                severity: Severity::Critical,
+                message: format!("{} 出现 {} 个不可纠正的 ECC 错误", name, uncorrected),
+            });
+        }
+
+        let hardware_throttle: Vec<&str> = gpu
+            .throttle_reasons
+            .iter()
+            .map(String::as_str)
+            .filter(|r| {
+                matches!(
+                    *r,
+                    "hw_slowdown" | "hw_thermal_slowdown" | "hw_power_brake_slowdown"
+                )
+            })
+            .collect();
+        if !hardware_throttle.is_empty() {
+            alerts.push(Alert {
+                kind: "gpu_throttle".to_string(),
+                severity: Severity::Warning,
+                message: format!("{} 被硬件降频: {}", name, hardware_throttle.join(", ")),
+            });
+        }
+    }
+}
+
+/// 温区达到触发点：passive 表示内核已开始降频，hot / critical 表示即将关机
+fn check_thermal(stats: &SystemStats, alerts: &mut Vec<Alert>) {
+    for zone in &stats.sensors.thermal_zones {
+        let Some(temp) = zone.temperature_celsius else {
+            continue;
+        };
+        let reached = |types: &[&str]| {
+            zone.trip_points
+                .iter()
+                .filter(|t| types.contains(&t.trip_type.as_str()))
+                .any(|t| temp >= t.temperature_celsius)
+        };
+
+        let severity = if reached(&["hot", "critical"]) {
+            Severity::Critical
+        } else if reached(&["passive"]) {
+            Severity::Warning
+        } else {
+            continue;
+        };
+        alerts.push(Alert {
+            kind: "thermal_trip".to_string(),
+            severity,
+            message: format!(
+                "温区 {} ({}) 温度 {:.1}°C 已达到触发点",
+                zone.name, zone.zone_type, temp
+            ),
+        });
+    }
+}
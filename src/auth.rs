@@ -1,46 +1,71 @@
 use axum::{
     Json, async_trait,
-    extract::{FromRequestParts, State},
+    extract::{FromRef, FromRequestParts, State},
     http::{StatusCode, request::Parts},
 };
 use bcrypt::{DEFAULT_COST, hash, verify};
 use chrono::{Duration, Utc};
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
 use std::sync::Arc;
 
-// JWT 密钥（生产环境应从环境变量读取）
-const JWT_SECRET: &[u8] = b"your-secret-key-change-this-in-production";
 const TOKEN_EXPIRE_HOURS: i64 = 24;
+// 随机生成的 JWT 密钥长度（字节）
+const RANDOM_SECRET_LEN: usize = 32;
 
 // 存储层（生产环境应使用 Redis/数据库）
 pub struct AuthState {
     pub users: Vec<(String, String)>, // (username, hashed_password)
+    // 管理员用户名（可执行目录扫描等操作）；None 表示未配置 MONITOR_ADMIN_USERS
+    pub admins: Option<Vec<String>>,
+    jwt_secret: Vec<u8>, // JWT 签名密钥
 }
 
 // 在 AuthState 中添加构造函数
 impl AuthState {
-    pub fn new() -> Self {
-        let hashed = hash("user123", DEFAULT_COST).unwrap();
+    pub fn new_with_credentials(
+        username: String,
+        password: String,
+        admins: Option<Vec<String>>,
+        jwt_secret: Vec<u8>,
+    ) -> Self {
+        let hashed = hash(&password, DEFAULT_COST).unwrap();
         Self {
-            users: vec![("user".to_string(), hashed)],
+            users: vec![(username, hashed)],
+            admins,
+            jwt_secret,
         }
     }
 
-    pub fn new_with_credentials(username: String, password: String) -> Self {
-        let hashed = hash(&password, DEFAULT_COST).unwrap();
-        Self {
-            users: vec![(username, hashed)],
+    /// 未配置管理员列表时，仅当只有一个用户时该用户默认为管理员；
+    /// 之后再添加用户不会让任何人隐式获得管理员权限，需显式配置
+    pub fn is_admin(&self, username: &str) -> bool {
+        match &self.admins {
+            Some(admins) => admins.iter().any(|a| a == username),
+            None => matches!(self.users.as_slice(), [(only, _)] if only == username),
         }
     }
 }
 
+/// 未配置 MONITOR_JWT_SECRET 时使用的随机密钥，重启后之前签发的 Token 全部失效
+pub fn random_secret() -> Vec<u8> {
+    let mut secret = vec![0u8; RANDOM_SECRET_LEN];
+    File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(&mut secret))
+        .expect("无法读取 /dev/urandom 生成 JWT 密钥");
+    secret
+}
+
 // JWT Claims 结构
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String, // 用户名
     pub exp: i64,    // 过期时间戳
     pub iat: i64,    // 签发时间戳
+    #[serde(default)]
+    pub admin: bool, // 是否为管理员（仅供前端显示，权限检查以服务端配置为准）
 }
 
 // 登录请求
@@ -90,12 +115,13 @@ pub async fn login(
                     sub: username.clone(),
                     exp: exp.timestamp(),
                     iat: now.timestamp(),
+                    admin: state.is_admin(username),
                 };
 
                 let token = encode(
                     &Header::default(),
                     &claims,
-                    &EncodingKey::from_secret(JWT_SECRET),
+                    &EncodingKey::from_secret(&state.jwt_secret),
                 )
                 .map_err(|_| {
                     (
@@ -129,15 +155,35 @@ pub async fn login(
     }
 }
 
+// 管理员权限检查（用于需要管理员的接口）
+// 按当前配置的管理员列表判断，而不是信任 Token 中的 admin 字段
+pub fn require_admin(
+    state: &AuthState,
+    claims: &Claims,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    if state.is_admin(&claims.sub) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: "需要管理员权限".to_string(),
+            }),
+        ))
+    }
+}
+
 // JWT 验证提取器（用于保护路由）
 #[async_trait]
 impl<S> FromRequestParts<S> for Claims
 where
+    Arc<AuthState>: FromRef<S>,
     S: Send + Sync,
 {
     type Rejection = (StatusCode, Json<ErrorResponse>);
 
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth = Arc::<AuthState>::from_ref(state);
         // 从 Header 提取 Token
         let auth_header = parts
             .headers
@@ -148,7 +194,11 @@ where
         match auth_header {
             Some(token) => {
                 let validation = Validation::default();
-                match decode::<Claims>(token, &DecodingKey::from_secret(JWT_SECRET), &validation) {
+                match decode::<Claims>(
+                    token,
+                    &DecodingKey::from_secret(&auth.jwt_secret),
+                    &validation,
+                ) {
                     Ok(token_data) => Ok(token_data.claims),
                     Err(_) => Err((
                         StatusCode::UNAUTHORIZED,
@@ -167,3 +217,62 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(sub: &str, admin: bool) -> Claims {
+        Claims {
+            sub: sub.to_string(),
+            exp: 0,
+            iat: 0,
+            admin,
+        }
+    }
+
+    #[test]
+    fn admin_is_checked_against_server_config() {
+        let state = AuthState {
+            users: Vec::new(),
+            admins: Some(vec!["root".to_string()]),
+            jwt_secret: random_secret(),
+        };
+        assert!(require_admin(&state, &claims("root", false)).is_ok());
+        // Token 中自称管理员无效
+        let (status, _) = require_admin(&state, &claims("user", true)).unwrap_err();
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn default_admin_only_for_single_user() {
+        let mut state = AuthState {
+            users: vec![("user".to_string(), String::new())],
+            admins: None,
+            jwt_secret: random_secret(),
+        };
+        assert!(state.is_admin("user"));
+        assert!(!state.is_admin("other"));
+
+        // 添加用户后不再有隐式管理员
+        state.users.push(("other".to_string(), String::new()));
+        assert!(!state.is_admin("user"));
+        assert!(!state.is_admin("other"));
+
+        state.admins = Some(vec!["other".to_string()]);
+        assert!(!state.is_admin("user"));
+        assert!(state.is_admin("other"));
+
+        // 显式配置为空表示没有管理员
+        state.users.truncate(1);
+        state.admins = Some(Vec::new());
+        assert!(!state.is_admin("user"));
+    }
+
+    #[test]
+    fn random_secrets_differ() {
+        let secret = random_secret();
+        assert_eq!(secret.len(), RANDOM_SECRET_LEN);
+        assert_ne!(secret, random_secret());
+    }
+}
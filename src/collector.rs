@@ -1,10 +1,27 @@
-use battery::{Manager as BatteryManager, State};
-use nvml_wrapper::Nvml;
-use nvml_wrapper::enums::device::UsedGpuMemory;
+use crate::alerts::{self, Alert};
+use crate::cpu::{self, CoreFrequency, CpuTopology};
+use crate::forecast::DiskForecast;
+use crate::gpu::{self, GpuInfo};
+use crate::memory::{self, MemoryDetails, PagingRates};
+use crate::network::{
+    self, InterfaceAddress, NetCounters, NetDevSnapshot, WirelessInfo, WirelessLinkCache,
+};
+use crate::oom::{self, OomStats, ProcessSnapshot};
+use crate::power::{self, PowerStats};
+use crate::power_supply::{self, BatteryInfo};
+use crate::probes::ProbeStatus;
+use crate::psi::{self, PressureStats};
+use crate::sampler::Sampler;
+use crate::sensors::{self, HardwareSensors};
+use crate::smart::{self, SmartInfo};
+use crate::storage::{self, BlockDeviceIo};
+use crate::storage_health::{self, StorageHealth};
+use chrono::Utc;
 use serde::Serialize;
-use std::fs;
+use std::collections::HashMap;
 use std::path::Path;
-use sysinfo::{Disks, Networks, ProcessStatus, System};
+use std::time::Instant;
+use sysinfo::{Disks, Pid, ProcessStatus, System};
 
 #[derive(Serialize, Clone)]
 pub struct SystemStats {
@@ -18,21 +35,40 @@ pub struct SystemStats {
     // CPU 进阶信息（新增）
     pub cpu_advanced: CpuAdvanced,
 
-    // GPU 信息
-    pub gpu: Option<GpuInfo>,
+    // 资源压力（PSI，仅 Linux）
+    pub pressure: PressureStats,
+
+    // 内存细分：交换区、缓存、大页、zram/zswap、分页速率
+    pub memory_details: MemoryDetails,
+
+    // OOM 击杀事件
+    pub oom: OomStats,
+
+    // 所有 GPU（按 PCI 地址排序，没有 GPU 时为空）
+    pub gpus: Vec<GpuInfo>,
 
     // 进程管理（新增）
     pub processes: Vec<ProcessInfo>,
 
     // 磁盘信息
     pub disks: Vec<DiskInfo>,
+    // 块设备 IO（吞吐、IOPS、延迟）
+    pub disk_io: Vec<BlockDeviceIo>,
+    // 软 RAID / LVM / ZFS 健康状态
+    pub storage_health: StorageHealth,
 
     // 网络进阶 + 硬件传感器（新增）
     pub network_advanced: NetworkAdvanced,
+    // 主动探测（TCP / HTTP / DNS / ICMP）的延迟和可达性
+    pub probes: Vec<ProbeStatus>,
     pub sensors: HardwareSensors,
+    pub power: PowerStats,
 
     // 电池信息
     pub battery: Option<BatteryInfo>,
+
+    // 当前生效的告警
+    pub alerts: Vec<Alert>,
 }
 
 #[derive(Serialize, Clone)]
@@ -41,11 +77,20 @@ pub struct ResourceBlock {
     pub cpu_usage: f32,
     pub cpu_count: usize,
     pub cpu_name: String,
+    pub cpu_sockets: usize,
+    pub cpu_physical_cores: usize,
 
     // 内存
     pub memory_total: f64,
+    // MemTotal − MemAvailable（GB），与 memory_details.used_mb 为同一口径
     pub memory_used: f64,
+    pub memory_available: f64,
     pub memory_usage_percent: f64,
+
+    // 交换区
+    pub swap_total: f64,
+    pub swap_used: f64,
+    pub swap_usage_percent: f64,
 }
 
 #[derive(Serialize, Clone)]
@@ -54,33 +99,16 @@ pub struct CpuAdvanced {
     pub per_core_usage: Vec<f32>,
     // CPU 频率
     pub cpu_frequency_mhz: u64,
+    // 每个核心的频率、调频策略和降频状态
+    pub per_core_frequency: Vec<CoreFrequency>,
+    // 插槽 / 物理核心 / 缓存 / NUMA 布局
+    pub topology: CpuTopology,
     // 负载均衡（1/5/15分钟）
     pub load_avg_1: f64,
     pub load_avg_5: f64,
     pub load_avg_15: f64,
 }
 
-#[derive(Serialize, Clone)]
-pub struct GpuInfo {
-    pub vendor: String, // 厂商：NVIDIA / AMD / Intel
-    pub name: String,
-    pub usage_percent: u32,
-    pub memory_total_mb: u64,
-    pub memory_used_mb: u64,
-    pub temperature: u32,
-    pub fan_speed_percent: Option<u32>,     // 风扇转速百分比
-    pub core_clock_mhz: Option<u32>,        // 核心频率
-    pub memory_clock_mhz: Option<u32>,      // 显存频率
-    pub top_processes: Vec<GpuProcessInfo>, // 占用显存的进程
-}
-
-#[derive(Serialize, Clone)]
-pub struct GpuProcessInfo {
-    pub pid: u32,
-    pub name: String,
-    pub memory_mb: u64,
-}
-
 #[derive(Serialize, Clone)]
 pub struct ProcessInfo {
     pub pid: u32,
@@ -97,13 +125,55 @@ pub struct DiskInfo {
     pub used_gb: f64,
     pub usage_percent: f64,
     pub mount_point: String,
+    // 所在块设备（对应 disk_io 中的 name），tmpfs 等虚拟文件系统为 None
+    pub device: Option<String>,
+    // 文件系统类型和挂载选项
+    pub fs_type: String,
+    pub mount_options: Vec<String>,
+    pub read_only: bool,
+    // 运行期间由读写变为只读（文件系统出错后被内核 remount）
+    pub remounted_read_only: bool,
+    pub no_exec: bool,
+    // inode 使用情况（btrfs 等不限制 inode 的文件系统为 None）
+    pub inodes_total: Option<u64>,
+    pub inodes_used: Option<u64>,
+    pub inodes_usage_percent: Option<f64>,
+    // 根据使用量历史预测的写满时间（历史不足时为 None）
+    pub forecast: Option<DiskForecast>,
+    // 所在物理磁盘的 SMART 信息（未启用 smartctl 或无法读取时为 None）
+    pub smart: Option<SmartInfo>,
+}
+
+/// 测试用的挂载点记录
+#[cfg(test)]
+impl DiskInfo {
+    pub fn fixture(mount_point: &str, used_gb: f64, read_only: bool) -> Self {
+        Self {
+            name: "/dev/sda1".to_string(),
+            total_gb: 100.0,
+            used_gb,
+            usage_percent: used_gb,
+            mount_point: mount_point.to_string(),
+            device: Some("sda1".to_string()),
+            fs_type: "ext4".to_string(),
+            mount_options: Vec::new(),
+            read_only,
+            remounted_read_only: false,
+            no_exec: false,
+            inodes_total: None,
+            inodes_used: None,
+            inodes_usage_percent: None,
+            forecast: None,
+            smart: None,
+        }
+    }
 }
 
 #[derive(Serialize, Clone)]
 pub struct NetworkAdvanced {
     // 网络接口及其流量
     pub interfaces: Vec<NetworkInterface>,
-    // 实时网速（需要计算，这里简化为当前值）
+    // 所有物理接口（有 /sys/class/net/<if>/device）的合计速率，不含回环和虚拟接口（与上次采样做差）
     pub download_speed_mbps: f64,
     pub upload_speed_mbps: f64,
 }
@@ -111,44 +181,40 @@ pub struct NetworkAdvanced {
 #[derive(Serialize, Clone)]
 pub struct NetworkInterface {
     pub name: String,
-    pub received_mb: u64,
-    pub transmitted_mb: u64,
-}
-
-#[derive(Serialize, Clone)]
-pub struct HardwareSensors {
-    // CPU 温度
-    pub cpu_temp_celsius: Option<f32>,
-    // 主板温度
-    pub motherboard_temp_celsius: Option<f32>,
-    // CPU 风扇转速
-    pub cpu_fan_rpm: Option<u32>,
-    // CPU 电压
-    pub cpu_voltage: Option<f32>,
+    // 没有对应物理设备的接口（lo、docker0、veth 等）
+    pub is_virtual: bool,
+    // 链路状态：up / down / dormant / unknown
+    pub operstate: Option<String>,
+    pub speed_mbps: Option<u64>,
+    pub duplex: Option<String>,
+    pub mtu: Option<u64>,
+    pub mac_address: Option<String>,
+    pub carrier_changes: Option<u64>,
+    // 所有 IPv4 / IPv6 地址及前缀长度
+    pub addresses: Vec<InterfaceAddress>,
+    // 无线网卡的信号质量、SSID 等，有线网卡为 None
+    pub wireless: Option<WirelessInfo>,
+    // 开机以来的累计字节数
+    pub received_bytes: u64,
+    pub transmitted_bytes: u64,
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    pub rx_packets_per_sec: f64,
+    pub tx_packets_per_sec: f64,
+    // 累计错误和丢包数
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+    // 本次采样间隔内新增的错误 / 丢包速率（收 + 发）
+    pub errors_per_sec: f64,
+    pub drops_per_sec: f64,
 }
 
-#[derive(Serialize, Clone)]
-pub struct BatteryInfo {
-    pub percentage: f32,
-    pub is_charging: bool,
-    pub time_remaining_minutes: Option<i64>,
-    pub health_percent: f32,
-}
-
-// 全局变量存储上次的网络数据（用于计算速度）
-static mut LAST_NETWORK_DATA: Option<(u64, u64, std::time::Instant)> = None;
-
-pub async fn collect_stats() -> SystemStats {
-    let mut sys = System::new_all();
-
-    // 首次刷新所有信息
-    sys.refresh_all();
-
-    // 关键修改：sysinfo 获取 CPU 使用率需要时间间隔
-    // 先刷新 CPU，等待 500ms，再次刷新才能拿到准确的 usage
-    sys.refresh_cpu();
-    std::thread::sleep(std::time::Duration::from_millis(500));
-    sys.refresh_cpu();
+pub fn collect_stats(sampler: &mut Sampler) -> SystemStats {
+    // 采样器持有的 System 在两次采样之间保持，CPU 占用率按采样间隔计算
+    sampler.sys.refresh_all();
+    let sys = &sampler.sys;
 
     // CPU 计算
     let cpus = sys.cpus();
@@ -160,41 +226,145 @@ pub async fn collect_stats() -> SystemStats {
 
     // CPU 进阶信息
     let per_core_usage: Vec<f32> = cpus.iter().map(|c| c.cpu_usage()).collect();
-    let cpu_frequency_mhz = cpus.get(0).map(|c| c.frequency()).unwrap_or(0);
+    let cpu_frequency_mhz = cpus.first().map(|c| c.frequency()).unwrap_or(0);
+    let per_core_frequency = cpu::collect_core_frequencies(Path::new(cpu::CPU_SYSFS_ROOT), cpus);
+    let topology = cpu::collect_topology(
+        Path::new(cpu::CPU_SYSFS_ROOT),
+        Path::new(cpu::NODE_SYSFS_ROOT),
+        cpus,
+    );
     let load_avg = System::load_average();
 
+    let cpu_sockets = topology.sockets;
+    let cpu_physical_cores = topology.physical_cores;
     let cpu_advanced = CpuAdvanced {
         per_core_usage,
         cpu_frequency_mhz,
+        per_core_frequency,
+        topology,
         load_avg_1: load_avg.one,
         load_avg_5: load_avg.five,
         load_avg_15: load_avg.fifteen,
     };
 
+    // 资源压力
+    let mut pressure = psi::collect_pressure(Path::new(psi::PSI_ROOT));
+    sampler
+        .pressure_history
+        .record(Utc::now().timestamp(), &mut pressure);
+
     // 内存计算
     let memory_total = sys.total_memory() as f64 / 1024.0 / 1024.0 / 1024.0;
     let memory_used = sys.used_memory() as f64 / 1024.0 / 1024.0 / 1024.0;
+    let memory_available = sys.available_memory() as f64 / 1024.0 / 1024.0 / 1024.0;
     let memory_usage_percent = if memory_total > 0.0 {
         (memory_used / memory_total) * 100.0
     } else {
         0.0
     };
+    let swap_total = sys.total_swap() as f64 / 1024.0 / 1024.0 / 1024.0;
+    let swap_used = sys.used_swap() as f64 / 1024.0 / 1024.0 / 1024.0;
+    let swap_usage_percent = if swap_total > 0.0 {
+        (swap_used / swap_total) * 100.0
+    } else {
+        0.0
+    };
 
-    // GPU 采集（自动检测）
-    let gpu = collect_gpu_info();
+    // 分页速率：与上次 vmstat 快照做差
+    let now = Instant::now();
+    let vmstat = memory::read_vmstat(Path::new(memory::VMSTAT_PATH));
+    let paging = match (&sampler.last_vmstat, &vmstat) {
+        (Some((previous, at)), Some(current)) => {
+            memory::paging_rates(previous, current, now.duration_since(*at).as_secs_f64())
+        }
+        _ => PagingRates::default(),
+    };
+    let memory_details = memory::collect_memory_details(
+        Path::new(memory::MEMINFO_PATH),
+        Path::new(memory::BLOCK_SYSFS_ROOT),
+        Path::new(memory::ZSWAP_ENABLED_PATH),
+        paging,
+    );
+
+    // GPU 采集（自动检测），进程名从 sysinfo 进程表解析
+    let process_name = |pid: u32| {
+        sys.process(Pid::from_u32(pid))
+            .map(|p| p.name().to_string())
+            .unwrap_or_else(|| format!("PID {}", pid))
+    };
+    let mut gpus = gpu::collect_gpus(
+        &sampler.config.drm_root,
+        sampler.nvml.as_deref(),
+        &sampler
+            .pcie_throughput
+            .read()
+            .map(|p| p.clone())
+            .unwrap_or_default(),
+        &process_name,
+    );
+    // 只有 AMD / Intel 等 DRM 显卡需要读取 fdinfo
+    let drm_clients = gpu::needs_drm_clients(&gpus).then(|| sampler.drm_clients.read(now));
+    if let Some(drm_clients) = &drm_clients {
+        gpu::attach_drm_processes(
+            &mut gpus,
+            sampler
+                .last_drm_clients
+                .as_ref()
+                .map(|(previous, at)| (previous, now.duration_since(*at).as_secs_f64())),
+            drm_clients,
+            &process_name,
+        );
+    }
 
     // 进程采集 - 传入 sys 对象
-    let processes = collect_process_info(&sys);
+    let processes = collect_process_info(sys);
 
-    // 磁盘
+    // OOM 检测：对比 oom_kill 计数，并在事件所属 cgroup 中消失的进程里确定被杀对象（跳过线程）
+    let process_snapshot: ProcessSnapshot = sys
+        .processes()
+        .iter()
+        .filter(|(_, process)| process.thread_kind().is_none())
+        .map(|(pid, process)| {
+            (
+                pid.as_u32(),
+                (
+                    process.name().to_string(),
+                    process.memory() as f64 / 1024.0 / 1024.0,
+                ),
+            )
+        })
+        .collect();
+    let oom = sampler.oom.update(
+        vmstat.as_ref(),
+        Path::new(oom::CGROUP_ROOT),
+        Path::new(oom::PROC_ROOT),
+        process_snapshot,
+    );
+
+    // 磁盘（按配置过滤伪文件系统和容器挂载）
     let disks = Disks::new_with_refreshed_list();
-    let disk_infos: Vec<DiskInfo> = disks
+    let mountinfo = storage::read_mountinfo(Path::new(storage::MOUNTINFO_PATH));
+    let mut disk_infos: Vec<DiskInfo> = disks
         .iter()
-        .map(|disk| {
+        .filter_map(|disk| {
+            let mount_point = disk.mount_point().to_string_lossy().to_string();
+            let mount = mountinfo.get(&mount_point);
+            let fs_type = mount
+                .map(|m| m.fs_type.clone())
+                .unwrap_or_else(|| disk.file_system().to_string_lossy().to_string());
+            if !sampler.config.disk_filter.matches(&fs_type, &mount_point) {
+                return None;
+            }
+            let mount_options = mount.map(|m| m.options.clone()).unwrap_or_default();
+            let has_option = |name: &str| mount_options.iter().any(|o| o == name);
+            let read_only = has_option("ro");
+            let no_exec = has_option("noexec");
+
+            let inodes = storage::inode_usage(disk.mount_point());
             let total = disk.total_space() as f64 / 1024.0 / 1024.0 / 1024.0;
             let available = disk.available_space() as f64 / 1024.0 / 1024.0 / 1024.0;
             let used = total - available;
-            DiskInfo {
+            Some(DiskInfo {
                 name: disk.name().to_string_lossy().to_string(),
                 total_gb: total,
                 used_gb: used,
@@ -203,238 +373,173 @@ pub async fn collect_stats() -> SystemStats {
                 } else {
                     0.0
                 },
-                mount_point: disk.mount_point().to_string_lossy().to_string(),
-            }
+                device: storage::resolve_block_device(&disk.name().to_string_lossy()),
+                mount_point,
+                fs_type,
+                read_only,
+                remounted_read_only: false,
+                no_exec,
+                mount_options,
+                inodes_total: inodes.as_ref().map(|i| i.total),
+                inodes_used: inodes.as_ref().map(|i| i.used),
+                inodes_usage_percent: inodes
+                    .as_ref()
+                    .map(|i| i.used as f64 / i.total as f64 * 100.0),
+                forecast: None,
+                smart: None,
+            })
         })
         .collect();
 
-    // 网络进阶
-    let network_advanced = collect_network_advanced();
+    sampler.read_only.update(&mut disk_infos);
+
+    // SMART：分区挂载点关联到所在整盘的检查结果
+    if let Ok(smart) = sampler.smart.read()
+        && !smart.is_empty()
+    {
+        for disk in &mut disk_infos {
+            disk.smart = disk.device.as_ref().and_then(|device| {
+                let parent = smart::parent_disk(Path::new(smart::CLASS_BLOCK_ROOT), device);
+                smart.get(&parent).cloned()
+            });
+        }
+    }
+
+    // 写满预测：记录使用量历史并拟合增长速度
+    let timestamp = Utc::now().timestamp();
+    sampler.disk_history.record(timestamp, &disk_infos);
+    for disk in &mut disk_infos {
+        disk.forecast = sampler.disk_history.forecast(disk, timestamp);
+    }
+
+    // 块设备 IO：与上次 diskstats 快照做差，并关联到上面的挂载点
+    let mut mounts: HashMap<String, Vec<String>> = HashMap::new();
+    for disk in &disk_infos {
+        if let Some(device) = &disk.device {
+            mounts
+                .entry(device.clone())
+                .or_default()
+                .push(disk.mount_point.clone());
+        }
+    }
+    let diskstats = storage::read_diskstats(Path::new(storage::DISKSTATS_PATH));
+    let disk_io = match &diskstats {
+        Some(current) => {
+            let (previous, elapsed) = match &sampler.last_diskstats {
+                Some((previous, at)) => (Some(previous), now.duration_since(*at).as_secs_f64()),
+                None => (None, 0.0),
+            };
+            storage::device_io(previous, current, elapsed, &mounts)
+        }
+        None => Vec::new(),
+    };
+
+    // 存储健康
+    let storage_health = storage_health::collect_storage_health(
+        Path::new(storage_health::MDSTAT_PATH),
+        Path::new(storage_health::BLOCK_SYSFS_ROOT),
+        Path::new(storage_health::ZFS_KSTAT_ROOT),
+    );
+
+    // 网络进阶：与上次 /proc/net/dev 快照做差
+    let net_dev = network::read_net_dev(Path::new(network::NET_DEV_PATH));
+    let network_advanced = match &net_dev {
+        Some(current) => {
+            let (previous, elapsed) = match &sampler.last_net_dev {
+                Some((previous, at)) => (Some(previous), now.duration_since(*at).as_secs_f64()),
+                None => (None, 0.0),
+            };
+            collect_network_advanced(
+                previous,
+                current,
+                elapsed,
+                sampler.config.include_virtual_interfaces,
+                &mut sampler.wireless_links,
+            )
+        }
+        None => NetworkAdvanced {
+            interfaces: Vec::new(),
+            download_speed_mbps: 0.0,
+            upload_speed_mbps: 0.0,
+        },
+    };
+
+    // CPU / 内存功耗（RAPL）
+    let rapl = power::read_rapl(Path::new(power::POWERCAP_ROOT));
+    let power = power::power_stats(
+        sampler
+            .last_rapl
+            .as_ref()
+            .map(|(previous, at)| (previous.as_slice(), now.duration_since(*at).as_secs_f64())),
+        &rapl,
+        &mut sampler.energy,
+    );
 
     // 硬件传感器
-    let sensors = collect_hardware_sensors();
+    let sensors = sensors::collect_hardware_sensors(
+        Path::new(sensors::HWMON_SYSFS_ROOT),
+        Path::new(sensors::THERMAL_SYSFS_ROOT),
+    );
 
     // 电池采集
-    let battery = collect_battery_info();
+    let battery = power_supply::collect_battery_info(
+        Path::new(power_supply::POWER_SUPPLY_ROOT),
+        &mut sampler.battery_history,
+        Utc::now().timestamp(),
+    );
 
-    SystemStats {
+    let mut stats = SystemStats {
         hostname: System::host_name().unwrap_or_else(|| "Unknown".to_string()),
         os_version: System::long_os_version().unwrap_or_else(|| "Unknown".to_string()),
         resources: ResourceBlock {
             cpu_usage,
             cpu_count: cpus.len(),
             cpu_name: cpus
-                .get(0)
+                .first()
                 .map(|c| c.brand().to_string())
                 .unwrap_or_else(|| "Unknown".to_string()),
+            cpu_sockets,
+            cpu_physical_cores,
             memory_total,
             memory_used,
+            memory_available,
             memory_usage_percent,
+            swap_total,
+            swap_used,
+            swap_usage_percent,
         },
         cpu_advanced,
-        gpu,
+        pressure,
+        memory_details,
+        oom,
+        gpus,
         processes,
         disks: disk_infos,
+        disk_io,
+        storage_health,
         network_advanced,
+        probes: sampler.probes.read().map(|p| p.clone()).unwrap_or_default(),
         sensors,
+        power,
         battery,
-    }
-}
-
-fn collect_gpu_info() -> Option<GpuInfo> {
-    // 尝试 NVIDIA
-    if let Some(info) = collect_nvidia_gpu() {
-        return Some(info);
-    }
-
-    // 尝试 AMD
-    if let Some(info) = collect_amd_gpu() {
-        return Some(info);
-    }
-
-    // 尝试 Intel
-    if let Some(info) = collect_intel_gpu() {
-        return Some(info);
-    }
-
-    None
-}
-
-fn collect_nvidia_gpu() -> Option<GpuInfo> {
-    match Nvml::init() {
-        Ok(nvml) => {
-            match nvml.device_by_index(0) {
-                Ok(device) => {
-                    let name = device.name().unwrap_or_else(|_| "Unknown GPU".to_string());
-                    let usage_percent = device.utilization_rates().map(|u| u.gpu).unwrap_or(0);
-                    let memory_info = device.memory_info().ok()?;
-                    let memory_total_mb = memory_info.total / 1024 / 1024;
-                    let memory_used_mb = memory_info.used / 1024 / 1024;
-                    let temperature = device
-                        .temperature(nvml_wrapper::enum_wrappers::device::TemperatureSensor::Gpu)
-                        .unwrap_or(0);
-
-                    // 风扇转速
-                    let fan_speed_percent = device.fan_speed(0).ok();
-
-                    // 时钟频率
-                    let core_clock_mhz = device
-                        .clock_info(nvml_wrapper::enum_wrappers::device::Clock::Graphics)
-                        .ok();
-                    let memory_clock_mhz = device
-                        .clock_info(nvml_wrapper::enum_wrappers::device::Clock::Memory)
-                        .ok();
-
-                    // 占用显存的进程（修复 API 兼容性）
-                    let top_processes = device
-                        .running_graphics_processes()
-                        .ok()
-                        .map(|processes| {
-                            processes
-                                .iter()
-                                .filter_map(|p| {
-                                    // 将 UsedGpuMemory 枚举转换为字节数
-                                    let memory_bytes = match p.used_gpu_memory {
-                                        UsedGpuMemory::Used(bytes) => bytes,
-                                        UsedGpuMemory::Unavailable => 0,
-                                    };
-
-                                    Some(GpuProcessInfo {
-                                        pid: p.pid,
-                                        name: format!("PID: {}", p.pid), // 该版本没有进程名，用 PID 代替
-                                        memory_mb: memory_bytes / 1024 / 1024,
-                                    })
-                                })
-                                .take(5)
-                                .collect()
-                        })
-                        .unwrap_or_default();
-
-                    Some(GpuInfo {
-                        vendor: "NVIDIA".to_string(),
-                        name,
-                        usage_percent,
-                        memory_total_mb,
-                        memory_used_mb,
-                        temperature,
-                        fan_speed_percent,
-                        core_clock_mhz,
-                        memory_clock_mhz,
-                        top_processes,
-                    })
-                }
-                Err(_) => None,
-            }
-        }
-        Err(_) => None,
-    }
-}
-
-fn collect_amd_gpu() -> Option<GpuInfo> {
-    // AMD GPU 通过 sysfs 读取
-    // 路径通常是 /sys/class/drm/card0/device/
-    let amd_path = Path::new("/sys/class/drm/card0/device");
+        alerts: Vec::new(),
+    };
+    stats.alerts = alerts::evaluate(&stats);
 
-    if !amd_path.exists() {
-        return None;
+    // 保存本次计数器快照，供下次计算速率
+    if let Some(vmstat) = vmstat {
+        sampler.last_vmstat = Some((vmstat, now));
     }
-
-    // 尝试读取基本信息
-    let name = fs::read_to_string(amd_path.join("product_name"))
-        .ok()
-        .map(|s| s.trim().to_string())
-        .unwrap_or_else(|| "AMD GPU".to_string());
-
-    // 读取频率
-    let core_clock_mhz = fs::read_to_string(amd_path.join("pp_dpm_sclk"))
-        .ok()
-        .and_then(|s| {
-            s.lines()
-                .filter_map(|line| {
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    if parts.len() >= 2 && line.contains('*') {
-                        parts[1]
-                            .trim_end_matches('M')
-                            .trim_end_matches('H')
-                            .trim_end_matches('z')
-                            .parse::<u32>()
-                            .ok()
-                    } else {
-                        None
-                    }
-                })
-                .next()
-        });
-
-    // 读取温度（需要转换）
-    let temperature = fs::read_to_string(Path::new(
-        "/sys/class/drm/card0/device/hwmon/hwmon1/temp1_input",
-    ))
-    .ok()
-    .and_then(|s| s.trim().parse::<u32>().ok())
-    .map(|temp| temp / 1000); // 毫度转摄氏度
-
-    // 读取风扇转速
-    let fan_speed_percent = fs::read_to_string(Path::new(
-        "/sys/class/drm/card0/device/hwmon/hwmon1/fan1_input",
-    ))
-    .ok()
-    .and_then(|s| s.trim().parse::<u32>().ok())
-    .and_then(|rpm| {
-        // 简化计算，假设最大3000RPM
-        let percent = (rpm as f32 / 3000.0 * 100.0) as u32;
-        Some(if percent > 100 { 100 } else { percent })
-    });
-
-    // AMD 占用率和显存需要更复杂的实现，这里简化
-    Some(GpuInfo {
-        vendor: "AMD".to_string(),
-        name,
-        usage_percent: 0, // 需要更复杂的计算
-        memory_total_mb: 0,
-        memory_used_mb: 0,
-        temperature: temperature.unwrap_or(0),
-        fan_speed_percent,
-        core_clock_mhz,
-        memory_clock_mhz: None,
-        top_processes: vec![], // AMD 需要通过其他方式获取
-    })
-}
-
-fn collect_intel_gpu() -> Option<GpuInfo> {
-    // Intel GPU 通过 sysfs 读取
-    let intel_path = Path::new("/sys/class/drm/card0");
-
-    if !intel_path.exists() {
-        return None;
+    if let Some(diskstats) = diskstats {
+        sampler.last_diskstats = Some((diskstats, now));
     }
-
-    // 读取设备信息
-    let device_path = intel_path.join("device");
-    if !device_path.exists() {
-        return None;
+    if let Some(net_dev) = net_dev {
+        sampler.last_net_dev = Some((net_dev, now));
     }
+    sampler.last_drm_clients = drm_clients.map(|clients| (clients, now));
+    sampler.last_rapl = Some((rapl, now));
 
-    let name = "Intel Integrated Graphics".to_string();
-
-    // 读取频率
-    let core_clock_mhz = fs::read_to_string(intel_path.join("gt_cur_freq_mhz"))
-        .ok()
-        .and_then(|s| s.trim().parse::<u32>().ok());
-
-    // Intel 集显没有专用显存，温度通常与 CPU 共享
-    Some(GpuInfo {
-        vendor: "Intel".to_string(),
-        name,
-        usage_percent: 0,
-        memory_total_mb: 0,
-        memory_used_mb: 0,
-        temperature: 0, // 改为 0，不是 None
-        fan_speed_percent: None,
-        core_clock_mhz,
-        memory_clock_mhz: None,
-        top_processes: vec![],
-    })
+    stats
 }
 
 fn collect_process_info(sys: &System) -> Vec<ProcessInfo> {
@@ -467,130 +572,83 @@ fn collect_process_info(sys: &System) -> Vec<ProcessInfo> {
     processes
 }
 
-fn collect_network_advanced() -> NetworkAdvanced {
-    let networks = Networks::new_with_refreshed_list();
-
-    let interfaces: Vec<NetworkInterface> = networks
+/// 根据两次 /proc/net/dev 快照计算每个接口的速率，没有上次快照时速率均为 0
+fn collect_network_advanced(
+    previous: Option<&NetDevSnapshot>,
+    current: &NetDevSnapshot,
+    elapsed_secs: f64,
+    include_virtual: bool,
+    wireless_links: &mut WirelessLinkCache,
+) -> NetworkAdvanced {
+    let net_root = Path::new(network::NET_SYSFS_ROOT);
+    let mut addresses = network::interface_addresses();
+    let mut wireless = network::read_wireless(Path::new(network::NET_WIRELESS_PATH));
+
+    let mut interfaces: Vec<NetworkInterface> = current
         .iter()
-        .map(|(name, data)| NetworkInterface {
-            name: name.to_string(),
-            received_mb: data.total_received() / 1024 / 1024,
-            transmitted_mb: data.total_transmitted() / 1024 / 1024,
+        .filter_map(|(name, now)| {
+            let is_virtual = network::is_virtual_interface(net_root, name);
+            if is_virtual && !include_virtual {
+                return None;
+            }
+
+            let before = previous.and_then(|p| p.get(name));
+            // 计数器回绕或接口重建时差值按 0 处理
+            let per_sec = |f: fn(&NetCounters) -> u64| match before {
+                Some(before) if elapsed_secs > 0.0 => {
+                    f(now).saturating_sub(f(before)) as f64 / elapsed_secs
+                }
+                _ => 0.0,
+            };
+
+            let link = network::read_link_info(net_root, name);
+            Some(NetworkInterface {
+                name: name.clone(),
+                is_virtual,
+                operstate: link.operstate,
+                speed_mbps: link.speed_mbps,
+                duplex: link.duplex,
+                mtu: link.mtu,
+                mac_address: link.mac_address,
+                carrier_changes: link.carrier_changes,
+                addresses: addresses.remove(name).unwrap_or_default(),
+                wireless: wireless.remove(name).map(|mut info| {
+                    wireless_links.fill(name, &mut info, Instant::now());
+                    info
+                }),
+                received_bytes: now.rx_bytes,
+                transmitted_bytes: now.tx_bytes,
+                rx_bytes_per_sec: per_sec(|c| c.rx_bytes),
+                tx_bytes_per_sec: per_sec(|c| c.tx_bytes),
+                rx_packets_per_sec: per_sec(|c| c.rx_packets),
+                tx_packets_per_sec: per_sec(|c| c.tx_packets),
+                rx_errors: now.rx_errors,
+                tx_errors: now.tx_errors,
+                rx_dropped: now.rx_dropped,
+                tx_dropped: now.tx_dropped,
+                errors_per_sec: per_sec(|c| c.rx_errors + c.tx_errors),
+                drops_per_sec: per_sec(|c| c.rx_dropped + c.tx_dropped),
+            })
         })
         .collect();
-
-    // 计算实时网速
-    let total_received: u64 = interfaces.iter().map(|i| i.received_mb).sum();
-    let total_transmitted: u64 = interfaces.iter().map(|i| i.transmitted_mb).sum();
-
-    let (download_speed, upload_speed) = unsafe {
-        if let Some((last_rx, last_tx, last_time)) = LAST_NETWORK_DATA {
-            let now = std::time::Instant::now();
-            let duration = now.duration_since(last_time).as_secs_f64();
-
-            if duration > 0.0 {
-                let dl_speed = (total_received - last_rx) as f64 / duration * 8.0 / 1000.0; // Mbps
-                let ul_speed = (total_transmitted - last_tx) as f64 / duration * 8.0 / 1000.0;
-
-                LAST_NETWORK_DATA = Some((total_received, total_transmitted, now));
-                (dl_speed, ul_speed)
-            } else {
-                (0.0, 0.0)
-            }
-        } else {
-            LAST_NETWORK_DATA =
-                Some((total_received, total_transmitted, std::time::Instant::now()));
-            (0.0, 0.0)
-        }
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    wireless_links.retain(|name| current.contains_key(name));
+
+    // 合计速率只统计物理接口：回环是本机内部流量，网桥、veth、隧道上的流量
+    // 最终也会经过物理接口，计入会重复
+    let total = |f: fn(&NetworkInterface) -> f64| {
+        interfaces
+            .iter()
+            .filter(|i| !i.is_virtual)
+            .map(f)
+            .sum::<f64>()
     };
+    let download_speed_mbps = total(|i| i.rx_bytes_per_sec) * 8.0 / 1_000_000.0;
+    let upload_speed_mbps = total(|i| i.tx_bytes_per_sec) * 8.0 / 1_000_000.0;
 
     NetworkAdvanced {
         interfaces,
-        download_speed_mbps: download_speed,
-        upload_speed_mbps: upload_speed,
+        download_speed_mbps,
+        upload_speed_mbps,
     }
 }
-
-fn collect_hardware_sensors() -> HardwareSensors {
-    // 尝试从 /sys/class/hwmon 读取
-    let hwmon_base = Path::new("/sys/class/hwmon");
-
-    let mut cpu_temp: Option<f32> = None;
-    let mut motherboard_temp: Option<f32> = None;
-    let mut cpu_fan: Option<u32> = None;
-    let mut cpu_voltage: Option<f32> = None;
-
-    if let Ok(entries) = fs::read_dir(hwmon_base) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            let name = fs::read_to_string(path.join("name"))
-                .ok()
-                .map(|s| s.trim().to_string())
-                .unwrap_or_default();
-
-            // CPU 温度
-            if name.contains("coretemp") || name.contains("k10temp") || name.contains("cpu") {
-                cpu_temp = fs::read_to_string(path.join("temp1_input"))
-                    .ok()
-                    .and_then(|s| s.trim().parse::<f32>().ok())
-                    .map(|t| t / 1000.0);
-            }
-
-            // 主板温度
-            if name.contains("acpitz") || name.contains("board") {
-                motherboard_temp = fs::read_to_string(path.join("temp1_input"))
-                    .ok()
-                    .and_then(|s| s.trim().parse::<f32>().ok())
-                    .map(|t| t / 1000.0);
-            }
-
-            // 风扇转速
-            cpu_fan = fs::read_to_string(path.join("fan1_input"))
-                .ok()
-                .and_then(|s| s.trim().parse::<u32>().ok());
-
-            // CPU 电压
-            cpu_voltage = fs::read_to_string(path.join("in1_input"))
-                .ok()
-                .and_then(|s| s.trim().parse::<f32>().ok())
-                .map(|v| v / 1000.0);
-        }
-    }
-
-    // 如果没找到，尝试从 thermal zone 读取
-    if cpu_temp.is_none() {
-        cpu_temp = fs::read_to_string("/sys/class/thermal/thermal_zone0/temp")
-            .ok()
-            .and_then(|s| s.trim().parse::<f32>().ok())
-            .map(|t| t / 1000.0);
-    }
-
-    HardwareSensors {
-        cpu_temp_celsius: cpu_temp,
-        motherboard_temp_celsius: motherboard_temp,
-        cpu_fan_rpm: cpu_fan,
-        cpu_voltage,
-    }
-}
-
-fn collect_battery_info() -> Option<BatteryInfo> {
-    let manager = BatteryManager::new().ok()?;
-    let mut batteries = manager.batteries().ok()?;
-    let battery = batteries.next()?.ok()?;
-
-    let percentage = battery.state_of_charge().value * 100.0;
-    let is_charging = matches!(battery.state(), State::Charging | State::Full);
-    let time_remaining_minutes = if is_charging {
-        None
-    } else {
-        battery.time_to_empty().map(|t| t.value as i64 / 60)
-    };
-    let health_percent = battery.state_of_health().value * 100.0;
-
-    Some(BatteryInfo {
-        percentage,
-        is_charging,
-        time_remaining_minutes,
-        health_percent,
-    })
-}
@@ -0,0 +1,73 @@
+//! 外部命令调用辅助函数
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+// 等待子进程退出时的轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// 运行命令并读取标准输出；超时未退出时杀掉子进程并返回 None
+/// 不关心退出码（smartctl 等工具的退出码是位掩码，非 0 时输出仍然有效）
+pub fn output_with_timeout(command: &mut Command, timeout: Duration) -> Option<Vec<u8>> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // 输出可能超过管道缓冲区，需要边运行边读取，否则子进程会阻塞在写入上
+    let mut stdout = child.stdout.take()?;
+    let reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout.read_to_end(&mut buf);
+        buf
+    });
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(POLL_INTERVAL),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+    reader.join().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returns_output_of_finished_command() {
+        let output = output_with_timeout(
+            Command::new("sh").args(["-c", "echo hello; exit 3"]),
+            Duration::from_secs(5),
+        );
+        assert_eq!(output.as_deref(), Some(&b"hello\n"[..]));
+    }
+
+    #[test]
+    fn kills_command_after_timeout() {
+        let started = Instant::now();
+        let output =
+            output_with_timeout(Command::new("sleep").arg("10"), Duration::from_millis(200));
+        assert!(output.is_none());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn missing_program() {
+        let output = output_with_timeout(
+            &mut Command::new("/nonexistent/program"),
+            Duration::from_secs(1),
+        );
+        assert!(output.is_none());
+    }
+}
@@ -0,0 +1,377 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+pub const PROC_ROOT: &str = "/proc";
+
+// 返回的连接数最多的远端地址个数
+const TOP_PEERS: usize = 10;
+
+#[derive(Serialize, Clone)]
+pub struct ConnectionTable {
+    // 监听中的 TCP 端口和已绑定的 UDP 端口
+    pub listening: Vec<ListeningSocket>,
+    // TCP 连接数（按状态），如 ESTABLISHED / TIME_WAIT / CLOSE_WAIT
+    pub tcp_states: BTreeMap<String, u64>,
+    pub tcp_total: u64,
+    pub udp_total: u64,
+    // 已建立连接数最多的远端地址
+    pub top_peers: Vec<PeerCount>,
+    // 非 root 运行时无法读取其他用户进程的 fd，这些 socket 的进程为空
+    pub unresolved_sockets: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ListeningSocket {
+    // tcp / tcp6 / udp / udp6
+    pub protocol: String,
+    pub address: String,
+    pub port: u16,
+    pub uid: u32,
+    pub pid: Option<u32>,
+    pub process_name: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct PeerCount {
+    pub address: String,
+    pub connections: u64,
+}
+
+/// /proc/net/{tcp,udp}[6] 中的一行
+pub struct SocketEntry {
+    pub local: (IpAddr, u16),
+    pub remote: (IpAddr, u16),
+    pub state: u8,
+    pub uid: u32,
+    pub inode: u64,
+}
+
+// TCP 状态码（include/net/tcp_states.h）
+const TCP_ESTABLISHED: u8 = 0x01;
+const TCP_CLOSE: u8 = 0x07;
+const TCP_LISTEN: u8 = 0x0A;
+
+fn tcp_state_name(state: u8) -> &'static str {
+    match state {
+        0x01 => "ESTABLISHED",
+        0x02 => "SYN_SENT",
+        0x03 => "SYN_RECV",
+        0x04 => "FIN_WAIT1",
+        0x05 => "FIN_WAIT2",
+        0x06 => "TIME_WAIT",
+        0x07 => "CLOSE",
+        0x08 => "CLOSE_WAIT",
+        0x09 => "LAST_ACK",
+        0x0A => "LISTEN",
+        0x0B => "CLOSING",
+        0x0C => "NEW_SYN_RECV",
+        _ => "UNKNOWN",
+    }
+}
+
+/// 采集连接表；需要遍历所有进程的 fd，开销较大，只在请求时调用
+pub fn collect_connections(proc_root: &Path) -> ConnectionTable {
+    let read = |name: &str| {
+        fs::read_to_string(proc_root.join("net").join(name))
+            .map(|content| parse_proc_net(&content))
+            .unwrap_or_default()
+    };
+    let tcp: Vec<(&str, SocketEntry)> = ["tcp", "tcp6"]
+        .into_iter()
+        .flat_map(|p| read(p).into_iter().map(move |e| (p, e)))
+        .collect();
+    let udp: Vec<(&str, SocketEntry)> = ["udp", "udp6"]
+        .into_iter()
+        .flat_map(|p| read(p).into_iter().map(move |e| (p, e)))
+        .collect();
+
+    let mut tcp_states: BTreeMap<String, u64> = BTreeMap::new();
+    let mut peers: HashMap<IpAddr, u64> = HashMap::new();
+    for (_, entry) in &tcp {
+        *tcp_states
+            .entry(tcp_state_name(entry.state).to_string())
+            .or_default() += 1;
+        if entry.state == TCP_ESTABLISHED {
+            *peers.entry(entry.remote.0).or_default() += 1;
+        }
+    }
+
+    // UDP 没有监听状态，未连接（CLOSE）且绑定了端口的视为在监听
+    let listening_entries: Vec<&(&str, SocketEntry)> = tcp
+        .iter()
+        .filter(|(_, e)| e.state == TCP_LISTEN)
+        .chain(
+            udp.iter()
+                .filter(|(_, e)| e.state == TCP_CLOSE && e.local.1 != 0),
+        )
+        .collect();
+
+    let owners = socket_owners(proc_root);
+    let mut unresolved_sockets = 0;
+    let mut listening: Vec<ListeningSocket> = listening_entries
+        .into_iter()
+        .map(|(protocol, entry)| {
+            let owner = owners.get(&entry.inode);
+            if owner.is_none() {
+                unresolved_sockets += 1;
+            }
+            ListeningSocket {
+                protocol: protocol.to_string(),
+                address: entry.local.0.to_string(),
+                port: entry.local.1,
+                uid: entry.uid,
+                pid: owner.map(|(pid, _)| *pid),
+                process_name: owner.map(|(_, name)| name.clone()),
+            }
+        })
+        .collect();
+    listening.sort_by(|a, b| (a.port, &a.protocol).cmp(&(b.port, &b.protocol)));
+
+    let mut top_peers: Vec<PeerCount> = peers
+        .into_iter()
+        .map(|(address, connections)| PeerCount {
+            address: address.to_string(),
+            connections,
+        })
+        .collect();
+    top_peers.sort_by(|a, b| {
+        b.connections
+            .cmp(&a.connections)
+            .then_with(|| a.address.cmp(&b.address))
+    });
+    top_peers.truncate(TOP_PEERS);
+
+    ConnectionTable {
+        listening,
+        tcp_states,
+        tcp_total: tcp.len() as u64,
+        udp_total: udp.len() as u64,
+        top_peers,
+        unresolved_sockets,
+    }
+}
+
+/// 解析 /proc/net/tcp 等文件，格式（首行为表头）：
+/// `sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode ...`
+/// 地址为十六进制，如 `0100007F:0035`（127.0.0.1:53）
+pub fn parse_proc_net(content: &str) -> Vec<SocketEntry> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+            Some(SocketEntry {
+                local: parse_socket_address(fields[1])?,
+                remote: parse_socket_address(fields[2])?,
+                state: u8::from_str_radix(fields[3], 16).ok()?,
+                uid: fields[7].parse().ok()?,
+                inode: fields[9].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// 内核把网络字节序的地址按 32 位字当作本机整数打印（x86 上每 4 字节顺序颠倒），
+/// 因此解析出的整数按本机字节序还原即为原始字节
+fn parse_socket_address(value: &str) -> Option<(IpAddr, u16)> {
+    let (addr, port) = value.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+
+    let words: Vec<u32> = (0..addr.len() / 8)
+        .map(|i| u32::from_str_radix(&addr[i * 8..i * 8 + 8], 16))
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let ip = match words.as_slice() {
+        [word] => IpAddr::V4(Ipv4Addr::from(word.to_ne_bytes())),
+        [a, b, c, d] => {
+            let mut bytes = [0u8; 16];
+            for (i, word) in [a, b, c, d].into_iter().enumerate() {
+                bytes[i * 4..i * 4 + 4].copy_from_slice(&word.to_ne_bytes());
+            }
+            let v6 = Ipv6Addr::from(bytes);
+            // IPv4 映射地址（::ffff:a.b.c.d）按 IPv4 显示
+            v6.to_ipv4_mapped()
+                .map(IpAddr::V4)
+                .unwrap_or(IpAddr::V6(v6))
+        }
+        _ => return None,
+    };
+    Some((ip, port))
+}
+
+/// 遍历 /proc/<pid>/fd，建立 socket inode -> (PID, 进程名) 的映射
+fn socket_owners(proc_root: &Path) -> HashMap<u64, (u32, String)> {
+    let mut owners = HashMap::new();
+    let Ok(entries) = fs::read_dir(proc_root) else {
+        return owners;
+    };
+
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        let mut name: Option<String> = None;
+        for fd in fds.flatten() {
+            // 链接目标形如 `socket:[12345]`
+            let Some(inode) = fs::read_link(fd.path()).ok().and_then(|target| {
+                target
+                    .to_str()?
+                    .strip_prefix("socket:[")?
+                    .strip_suffix(']')?
+                    .parse::<u64>()
+                    .ok()
+            }) else {
+                continue;
+            };
+            let name = name.get_or_insert_with(|| {
+                fs::read_to_string(entry.path().join("comm"))
+                    .map(|s| s.trim().to_string())
+                    .unwrap_or_default()
+            });
+            owners.entry(inode).or_insert((pid, name.clone()));
+        }
+    }
+    owners
+}
+
+// /proc/net 中的地址按本机字节序打印，以下样例取自 x86_64
+#[cfg(all(test, target_endian = "little"))]
+mod tests {
+    use super::*;
+    use crate::sysfs::FixtureTree;
+
+    const TCP: &str = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0CEA 00000000:0000 0A 00000000:00000000 00:00000000 00000000   113        0 23456 1 0000000000000000 100 0 0 10 0
+   1: 0F02000A:0016 0202000A:D4C2 01 00000000:00000000 02:000A7D1C 00000000     0        0 34567 4 0000000000000000 20 4 29 10 -1
+   2: 0F02000A:0016 0202000A:D4C4 01 00000000:00000000 02:000A7D1C 00000000     0        0 34568 4 0000000000000000 20 4 29 10 -1
+   3: 0F02000A:A1B2 2278D9AC:01BB 06 00000000:00000000 03:00001770 00000000     0        0 0 3 0000000000000000
+";
+
+    const TCP6: &str = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000000000000:0050 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000    33        0 45678 1 0000000000000000 100 0 0 10 0
+   1: 0000000000000000FFFF00000A01A8C0:1F90 0000000000000000FFFF00001401A8C0:C350 01 00000000:00000000 02:00000B6E 00000000  1000        0 56789 1 0000000000000000 20 4 30 10 -1
+   2: B80D0120000000000000000001000000:0016 B80D0120000000000000000002000000:E2A4 01 00000000:00000000 02:00000B6E 00000000     0        0 56790 1 0000000000000000 20 4 30 10 -1
+";
+
+    const UDP: &str = "   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  123: 3500007F:0035 00000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 15678 2 0000000000000000 0
+  124: 0F02000A:B0C1 08080808:0035 01 00000000:00000000 00:00000000 00000000  1000        0 15679 2 0000000000000000 0
+";
+
+    fn addr(ip: &str, port: u16) -> (IpAddr, u16) {
+        (ip.parse().unwrap(), port)
+    }
+
+    #[test]
+    fn parses_tcp_listen_and_established_rows() {
+        let entries = parse_proc_net(TCP);
+        assert_eq!(entries.len(), 4);
+
+        let listen = &entries[0];
+        assert_eq!(listen.local, addr("127.0.0.1", 3306));
+        assert_eq!(listen.remote, addr("0.0.0.0", 0));
+        assert_eq!(listen.state, TCP_LISTEN);
+        assert_eq!(listen.uid, 113);
+        assert_eq!(listen.inode, 23456);
+
+        let established = &entries[1];
+        assert_eq!(established.local, addr("10.0.2.15", 22));
+        assert_eq!(established.remote, addr("10.0.2.2", 54466));
+        assert_eq!(established.state, TCP_ESTABLISHED);
+        assert_eq!(tcp_state_name(entries[3].state), "TIME_WAIT");
+    }
+
+    #[test]
+    fn parses_tcp6_and_ipv4_mapped_addresses() {
+        let entries = parse_proc_net(TCP6);
+        assert_eq!(entries[0].local, addr("::", 80));
+        // ::ffff:192.168.1.10 按 IPv4 显示
+        assert_eq!(entries[1].local, addr("192.168.1.10", 8080));
+        assert_eq!(entries[1].remote, addr("192.168.1.20", 50000));
+        assert_eq!(entries[2].local, addr("2001:db8::1", 22));
+        assert_eq!(entries[2].remote, addr("2001:db8::2", 58020));
+    }
+
+    #[test]
+    fn parses_udp_rows() {
+        let entries = parse_proc_net(UDP);
+        assert_eq!(entries[0].local, addr("127.0.0.53", 53));
+        assert_eq!(entries[0].state, TCP_CLOSE);
+        assert_eq!(entries[1].remote, addr("8.8.8.8", 53));
+    }
+
+    #[test]
+    fn rejects_malformed_addresses() {
+        assert!(parse_socket_address("0100007F").is_none());
+        assert!(parse_socket_address("0100007:0035").is_none());
+        assert!(parse_socket_address("ZZ00007F:0035").is_none());
+        assert!(parse_socket_address("0100007F:GGGG").is_none());
+        // 表头和字段不足的行跳过
+        assert!(parse_proc_net("sl local_address\n   0: 0100007F:0035\n").is_empty());
+    }
+
+    #[test]
+    fn maps_socket_inodes_to_processes() {
+        let tree = FixtureTree::new("socket-owners");
+        tree.write("100/comm", "mysqld\n")
+            .symlink("100/fd/3", "socket:[23456]")
+            .symlink("100/fd/4", "/var/lib/mysql/ibdata1")
+            .write("200/comm", "sshd\n")
+            .symlink("200/fd/5", "socket:[34567]")
+            .symlink("200/fd/6", "pipe:[999]");
+
+        let owners = socket_owners(&tree.root);
+        assert_eq!(owners.len(), 2);
+        assert_eq!(owners[&23456], (100, "mysqld".to_string()));
+        assert_eq!(owners[&34567], (200, "sshd".to_string()));
+    }
+
+    #[test]
+    fn collects_connection_table() {
+        let tree = FixtureTree::new("connections");
+        tree.write("net/tcp", TCP)
+            .write("net/tcp6", TCP6)
+            .write("net/udp", UDP)
+            .write("100/comm", "mysqld\n")
+            .symlink("100/fd/3", "socket:[23456]");
+
+        let table = collect_connections(&tree.root);
+        assert_eq!(table.tcp_total, 7);
+        assert_eq!(table.udp_total, 2);
+        assert_eq!(table.tcp_states["ESTABLISHED"], 4);
+        assert_eq!(table.tcp_states["LISTEN"], 2);
+        assert_eq!(table.tcp_states["TIME_WAIT"], 1);
+
+        let listening: Vec<_> = table
+            .listening
+            .iter()
+            .map(|s| (&*s.protocol, &*s.address, s.port, s.process_name.as_deref()))
+            .collect();
+        assert_eq!(
+            listening,
+            vec![
+                ("udp", "127.0.0.53", 53, None),
+                ("tcp6", "::", 80, None),
+                ("tcp", "127.0.0.1", 3306, Some("mysqld")),
+            ]
+        );
+        assert_eq!(table.unresolved_sockets, 2);
+
+        assert_eq!(table.top_peers[0].address, "10.0.2.2");
+        assert_eq!(table.top_peers[0].connections, 2);
+        assert_eq!(table.top_peers.len(), 3);
+    }
+}
@@ -0,0 +1,410 @@
+use crate::sysfs::{indexed_entries, parse_cpu_list, read_string, read_u64};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use sysinfo::Cpu;
+
+// sysfs 中 CPU 相关信息的根目录
+pub const CPU_SYSFS_ROOT: &str = "/sys/devices/system/cpu";
+// NUMA 节点信息的根目录
+pub const NODE_SYSFS_ROOT: &str = "/sys/devices/system/node";
+
+// 占用率超过该值视为"满载"，此时频率低于基准频率即认为在降频
+const THROTTLE_LOAD_PERCENT: f32 = 80.0;
+
+#[derive(Serialize, Clone)]
+pub struct CoreFrequency {
+    pub core: usize,
+    // 当前频率（优先读 cpufreq，读不到时退回 sysinfo）
+    pub current_mhz: u64,
+    // 调度器允许的频率范围
+    pub min_mhz: Option<u64>,
+    pub max_mhz: Option<u64>,
+    // 基准频率（不含睿频），部分驱动不提供
+    pub base_mhz: Option<u64>,
+    // 调频策略：performance / powersave / schedutil ...
+    pub governor: Option<String>,
+    // 温度墙触发次数（thermal_throttle，仅 Intel）
+    pub core_throttle_count: Option<u64>,
+    pub package_throttle_count: Option<u64>,
+    // 满载时频率低于基准频率
+    pub throttled: bool,
+}
+
+/// 采集每个核心的频率、调频策略和降频计数
+pub fn collect_core_frequencies(root: &Path, cpus: &[Cpu]) -> Vec<CoreFrequency> {
+    cpus.iter()
+        .enumerate()
+        .map(|(core, cpu)| {
+            // sysinfo 的核心名称就是 sysfs 目录名（cpu0、cpu1 ...）
+            let cpu_dir = root.join(cpu.name());
+            let cpufreq = cpu_dir.join("cpufreq");
+            let throttle = cpu_dir.join("thermal_throttle");
+
+            // cpufreq 中的频率单位是 kHz
+            let khz_to_mhz = |file: &str| read_u64(cpufreq.join(file)).map(|khz| khz / 1000);
+
+            let current_mhz = khz_to_mhz("scaling_cur_freq").unwrap_or_else(|| cpu.frequency());
+            let min_mhz = khz_to_mhz("scaling_min_freq").or_else(|| khz_to_mhz("cpuinfo_min_freq"));
+            let max_mhz = khz_to_mhz("scaling_max_freq").or_else(|| khz_to_mhz("cpuinfo_max_freq"));
+
+            // intel_pstate 提供 base_frequency，amd-pstate 提供 nominal 频率
+            let base_mhz =
+                khz_to_mhz("base_frequency").or_else(|| khz_to_mhz("amd_pstate_nominal_freq"));
+
+            let throttled = match base_mhz {
+                Some(base) => cpu.cpu_usage() >= THROTTLE_LOAD_PERCENT && current_mhz < base,
+                None => false,
+            };
+
+            CoreFrequency {
+                core,
+                current_mhz,
+                min_mhz,
+                max_mhz,
+                base_mhz,
+                governor: read_string(cpufreq.join("scaling_governor")),
+                core_throttle_count: read_u64(throttle.join("core_throttle_count")),
+                package_throttle_count: read_u64(throttle.join("package_throttle_count")),
+                throttled,
+            }
+        })
+        .collect()
+}
+
+#[derive(Serialize, Clone)]
+pub struct CpuTopology {
+    // 物理 CPU（插槽）数量
+    pub sockets: usize,
+    // 物理核心数量（不含超线程）
+    pub physical_cores: usize,
+    // 逻辑 CPU 数量（含超线程）
+    pub logical_cpus: usize,
+    // 每个物理核心的线程数，大于 1 表示开启了 SMT/超线程
+    pub threads_per_core: usize,
+    // 每个逻辑 CPU 所属的插槽、核心和 NUMA 节点
+    pub cpus: Vec<LogicalCpu>,
+    // 各级缓存（按实例去重）
+    pub caches: Vec<CacheInfo>,
+    // NUMA 节点，非 NUMA 机器通常只有 node0
+    pub numa_nodes: Vec<NumaNode>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct LogicalCpu {
+    pub cpu: usize,
+    pub package_id: Option<u64>,
+    pub core_id: Option<u64>,
+    pub numa_node: Option<usize>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CacheInfo {
+    pub level: u64,
+    // Data / Instruction / Unified
+    pub cache_type: String,
+    // 单个实例的大小
+    pub size_kb: u64,
+    // 该级缓存的实例数（如每核一个 L2，则等于核心数）
+    pub instances: usize,
+}
+
+#[derive(Serialize, Clone)]
+pub struct NumaNode {
+    pub node: usize,
+    pub cpus: Vec<usize>,
+    // 节点内逻辑 CPU 的平均占用率
+    pub cpu_usage: f32,
+    pub memory_total_mb: u64,
+    pub memory_used_mb: u64,
+    pub memory_usage_percent: f64,
+}
+
+/// 从 sysfs 读取 CPU 拓扑、缓存和 NUMA 布局
+pub fn collect_topology(cpu_root: &Path, node_root: &Path, cpus: &[Cpu]) -> CpuTopology {
+    // 逻辑 CPU 编号 -> 占用率
+    let usage: HashMap<usize, f32> = cpus
+        .iter()
+        .filter_map(|cpu| {
+            Some((
+                cpu.name().strip_prefix("cpu")?.parse().ok()?,
+                cpu.cpu_usage(),
+            ))
+        })
+        .collect();
+
+    let numa_nodes = collect_numa_nodes(node_root, &usage);
+    let node_of: HashMap<usize, usize> = numa_nodes
+        .iter()
+        .flat_map(|node| node.cpus.iter().map(move |&cpu| (cpu, node.node)))
+        .collect();
+
+    let mut logical = Vec::new();
+    // 缓存以 (级别, 类型, 共享 CPU 列表) 去重，同一实例被多个 CPU 共享
+    let mut cache_instances: BTreeSet<(u64, String, u64, String)> = BTreeSet::new();
+
+    for (cpu, path) in indexed_entries(cpu_root, "cpu") {
+        let topology = path.join("topology");
+        // 离线的 CPU 没有 topology 目录
+        if !topology.exists() {
+            continue;
+        }
+
+        logical.push(LogicalCpu {
+            cpu,
+            package_id: read_u64(topology.join("physical_package_id")),
+            core_id: read_u64(topology.join("core_id")),
+            numa_node: node_of.get(&cpu).copied(),
+        });
+
+        for (_, index) in indexed_entries(&path.join("cache"), "index") {
+            let (Some(level), Some(cache_type), Some(size), Some(shared)) = (
+                read_u64(index.join("level")),
+                read_string(index.join("type")),
+                read_string(index.join("size")).and_then(|s| parse_cache_size(&s)),
+                read_string(index.join("shared_cpu_list")),
+            ) else {
+                continue;
+            };
+            cache_instances.insert((level, cache_type, size, shared));
+        }
+    }
+
+    let sockets = logical
+        .iter()
+        .filter_map(|c| c.package_id)
+        .collect::<BTreeSet<_>>()
+        .len()
+        .max(1);
+    let physical_cores = logical
+        .iter()
+        .filter_map(|c| Some((c.package_id?, c.core_id?)))
+        .collect::<BTreeSet<_>>()
+        .len();
+    let physical_cores = if physical_cores == 0 {
+        logical.len()
+    } else {
+        physical_cores
+    };
+    let threads_per_core = logical
+        .len()
+        .checked_div(physical_cores)
+        .unwrap_or(1)
+        .max(1);
+
+    // 同级同类型缓存合并为一条，记录实例数
+    let mut caches: Vec<CacheInfo> = Vec::new();
+    for (level, cache_type, size_kb, _) in cache_instances {
+        match caches
+            .iter_mut()
+            .find(|c| c.level == level && c.cache_type == cache_type && c.size_kb == size_kb)
+        {
+            Some(cache) => cache.instances += 1,
+            None => caches.push(CacheInfo {
+                level,
+                cache_type,
+                size_kb,
+                instances: 1,
+            }),
+        }
+    }
+
+    CpuTopology {
+        sockets,
+        physical_cores,
+        logical_cpus: logical.len(),
+        threads_per_core,
+        cpus: logical,
+        caches,
+        numa_nodes,
+    }
+}
+
+fn collect_numa_nodes(node_root: &Path, usage: &HashMap<usize, f32>) -> Vec<NumaNode> {
+    indexed_entries(node_root, "node")
+        .into_iter()
+        .map(|(node, path)| {
+            let cpus = read_string(path.join("cpulist"))
+                .map(|list| parse_cpu_list(&list))
+                .unwrap_or_default();
+
+            let node_usage: Vec<f32> = cpus
+                .iter()
+                .filter_map(|cpu| usage.get(cpu))
+                .copied()
+                .collect();
+            let cpu_usage = if node_usage.is_empty() {
+                0.0
+            } else {
+                node_usage.iter().sum::<f32>() / node_usage.len() as f32
+            };
+
+            let (memory_total_kb, memory_used_kb) = fs::read_to_string(path.join("meminfo"))
+                .map(|content| parse_node_meminfo(&content))
+                .unwrap_or((0, 0));
+
+            NumaNode {
+                node,
+                cpus,
+                cpu_usage,
+                memory_total_mb: memory_total_kb / 1024,
+                memory_used_mb: memory_used_kb / 1024,
+                memory_usage_percent: if memory_total_kb > 0 {
+                    memory_used_kb as f64 / memory_total_kb as f64 * 100.0
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect()
+}
+
+/// 解析节点 meminfo，格式：`Node 0 MemTotal:  16384 kB`，返回 (总量, 已用) kB
+fn parse_node_meminfo(content: &str) -> (u64, u64) {
+    let mut total = 0;
+    let mut used = 0;
+    for line in content.lines() {
+        let mut parts = line.split_whitespace().skip(2);
+        let (Some(key), Some(value)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let value = value.parse().unwrap_or(0);
+        match key {
+            "MemTotal:" => total = value,
+            "MemUsed:" => used = value,
+            _ => {}
+        }
+    }
+    (total, used)
+}
+
+/// 解析缓存大小，如 `32K`、`16M`，返回 KB
+fn parse_cache_size(size: &str) -> Option<u64> {
+    let size = size.trim();
+    if let Some(kb) = size.strip_suffix('K') {
+        kb.parse().ok()
+    } else if let Some(mb) = size.strip_suffix('M') {
+        mb.parse::<u64>().ok().map(|mb| mb * 1024)
+    } else {
+        size.parse::<u64>().ok().map(|bytes| bytes / 1024)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::FixtureTree;
+
+    #[test]
+    fn parses_cache_sizes() {
+        assert_eq!(parse_cache_size("48K\n"), Some(48));
+        assert_eq!(parse_cache_size("30M"), Some(30 * 1024));
+        assert_eq!(parse_cache_size("65536"), Some(64));
+        assert_eq!(parse_cache_size("1G"), None);
+    }
+
+    #[test]
+    fn parses_node_meminfo() {
+        let content = "Node 1 MemTotal:       65855636 kB\n\
+                       Node 1 MemFree:        60012345 kB\n\
+                       Node 1 MemUsed:         5843291 kB\n\
+                       Node 1 Active:          1234567 kB\n";
+        assert_eq!(parse_node_meminfo(content), (65855636, 5843291));
+        assert_eq!(parse_node_meminfo(""), (0, 0));
+    }
+
+    /// 写入一个逻辑 CPU 的拓扑和缓存：每核独立的 L1d / L2，同一插槽共享 L3
+    fn logical_cpu(tree: &FixtureTree, cpu: usize, package: u64, core: u64, siblings: &str) {
+        let dir = format!("cpu/cpu{}", cpu);
+        tree.write(
+            &format!("{}/topology/physical_package_id", dir),
+            &package.to_string(),
+        )
+        .write(&format!("{}/topology/core_id", dir), &core.to_string());
+        let l3_shared = if package == 0 { "0-3" } else { "4-7" };
+        for (index, level, cache_type, size, shared) in [
+            (0, 1, "Data", "48K", siblings),
+            (1, 2, "Unified", "1280K", siblings),
+            (2, 3, "Unified", "30M", l3_shared),
+        ] {
+            let cache = format!("{}/cache/index{}", dir, index);
+            tree.write(&format!("{}/level", cache), &level.to_string())
+                .write(&format!("{}/type", cache), cache_type)
+                .write(&format!("{}/size", cache), size)
+                .write(&format!("{}/shared_cpu_list", cache), shared);
+        }
+    }
+
+    #[test]
+    fn collects_topology_and_dedups_caches() {
+        let tree = FixtureTree::new("cpu-topology");
+        // 2 插槽 × 2 核 × 2 线程，同一核心的两个线程编号相差 2
+        for cpu in 0..8 {
+            let package = (cpu / 4) as u64;
+            let core = (cpu % 2) as u64;
+            let first = cpu - cpu % 4 + cpu % 2;
+            logical_cpu(
+                &tree,
+                cpu,
+                package,
+                core,
+                &format!("{},{}", first, first + 2),
+            );
+        }
+        // 离线的 CPU 没有 topology 目录
+        tree.write("cpu/cpu8/online", "0\n")
+            .write("node/node0/cpulist", "0-3\n")
+            .write("node/node1/cpulist", "4-7\n");
+
+        let topology = collect_topology(&tree.root.join("cpu"), &tree.root.join("node"), &[]);
+        assert_eq!(topology.sockets, 2);
+        assert_eq!(topology.physical_cores, 4);
+        assert_eq!(topology.logical_cpus, 8);
+        assert_eq!(topology.threads_per_core, 2);
+        assert_eq!(topology.cpus[5].package_id, Some(1));
+        assert_eq!(topology.cpus[5].numa_node, Some(1));
+
+        let caches: Vec<_> = topology
+            .caches
+            .iter()
+            .map(|c| (c.level, &*c.cache_type, c.size_kb, c.instances))
+            .collect();
+        assert_eq!(
+            caches,
+            vec![
+                (1, "Data", 48, 4),
+                (2, "Unified", 1280, 4),
+                (3, "Unified", 30 * 1024, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn collects_numa_node_usage() {
+        let tree = FixtureTree::new("cpu-numa");
+        tree.write("node0/cpulist", "0-1,4\n")
+            .write(
+                "node0/meminfo",
+                "Node 0 MemTotal:  8388608 kB\nNode 0 MemUsed:  2097152 kB\n",
+            )
+            // 没有 CPU 的内存节点（如 CXL 内存）
+            .write("node1/cpulist", "\n")
+            .write(
+                "node1/meminfo",
+                "Node 1 MemTotal:  4194304 kB\nNode 1 MemUsed:  0 kB\n",
+            );
+        let usage = HashMap::from([(0, 10.0), (1, 20.0), (4, 60.0), (5, 100.0)]);
+
+        let nodes = collect_numa_nodes(&tree.root, &usage);
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].cpus, vec![0, 1, 4]);
+        assert_eq!(nodes[0].cpu_usage, 30.0);
+        assert_eq!(nodes[0].memory_total_mb, 8192);
+        assert_eq!(nodes[0].memory_used_mb, 2048);
+        assert_eq!(nodes[0].memory_usage_percent, 25.0);
+        assert!(nodes[1].cpus.is_empty());
+        assert_eq!(nodes[1].cpu_usage, 0.0);
+        assert_eq!(nodes[1].memory_usage_percent, 0.0);
+    }
+}
@@ -0,0 +1,484 @@
+use chrono::Utc;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fs::{self, Metadata};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+// 默认和最大遍历深度
+const DEFAULT_MAX_DEPTH: usize = 16;
+const MAX_DEPTH_LIMIT: usize = 64;
+// 默认和最大扫描时长（秒）
+const DEFAULT_TIME_LIMIT_SECS: u64 = 60;
+const MAX_TIME_LIMIT_SECS: u64 = 600;
+// 结果中保留的最大目录 / 文件数
+const TOP_ENTRIES: usize = 20;
+// 保留最近几次扫描结果
+const MAX_KEPT_SCANS: usize = 10;
+// 扫描过程中同步进度的间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanState {
+    Running,
+    Completed,
+    // 扫描线程异常退出，结果不可用
+    Failed,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ScanEntry {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ScanStatus {
+    pub id: u64,
+    pub path: String,
+    pub state: ScanState,
+    pub max_depth: usize,
+    pub time_limit_secs: u64,
+    // 开始 / 结束时间（Unix 秒）
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    pub elapsed_secs: f64,
+    // 进度：已扫描的文件、目录数和累计占用空间
+    pub files_scanned: u64,
+    pub dirs_scanned: u64,
+    pub total_bytes: u64,
+    // 无权限等原因无法读取的条目数
+    pub errors: u64,
+    pub current_path: Option<String>,
+    // 因超时或超过深度而未扫描完整，此时大小偏小
+    pub truncated: bool,
+    pub truncated_reason: Option<String>,
+    // 失败原因（仅 Failed 状态）
+    pub error: Option<String>,
+    // 占用空间最大的目录（含子目录）和文件，完成后填充
+    pub largest_dirs: Vec<ScanEntry>,
+    pub largest_files: Vec<ScanEntry>,
+}
+
+pub enum StartError {
+    // 路径不存在、不是绝对路径或不是目录
+    InvalidPath,
+    // 已有扫描正在进行（同时只允许一个，避免磁盘 IO 压力叠加）
+    Busy,
+}
+
+/// 目录扫描任务列表，按启动顺序保留最近几次
+pub struct DiskScans {
+    inner: Mutex<ScansInner>,
+}
+
+struct ScansInner {
+    next_id: u64,
+    scans: VecDeque<Arc<Mutex<ScanStatus>>>,
+}
+
+impl DiskScans {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(ScansInner {
+                next_id: 1,
+                scans: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// 启动后台扫描，立即返回初始状态
+    pub fn start(
+        &self,
+        path: &str,
+        max_depth: Option<usize>,
+        time_limit_secs: Option<u64>,
+    ) -> Result<ScanStatus, StartError> {
+        let path = Path::new(path);
+        if !path.is_absolute() {
+            return Err(StartError::InvalidPath);
+        }
+        let root = fs::canonicalize(path).map_err(|_| StartError::InvalidPath)?;
+        let metadata = fs::metadata(&root).map_err(|_| StartError::InvalidPath)?;
+        if !metadata.is_dir() {
+            return Err(StartError::InvalidPath);
+        }
+
+        let mut inner = lock(&self.inner);
+        let busy = inner
+            .scans
+            .iter()
+            .any(|s| lock(s).state == ScanState::Running);
+        if busy {
+            return Err(StartError::Busy);
+        }
+
+        let max_depth = max_depth.unwrap_or(DEFAULT_MAX_DEPTH).min(MAX_DEPTH_LIMIT);
+        let time_limit_secs = time_limit_secs
+            .unwrap_or(DEFAULT_TIME_LIMIT_SECS)
+            .clamp(1, MAX_TIME_LIMIT_SECS);
+        let status = ScanStatus {
+            id: inner.next_id,
+            path: root.to_string_lossy().to_string(),
+            state: ScanState::Running,
+            max_depth,
+            time_limit_secs,
+            started_at: Utc::now().timestamp(),
+            finished_at: None,
+            elapsed_secs: 0.0,
+            files_scanned: 0,
+            dirs_scanned: 0,
+            total_bytes: 0,
+            errors: 0,
+            current_path: None,
+            truncated: false,
+            truncated_reason: None,
+            error: None,
+            largest_dirs: Vec::new(),
+            largest_files: Vec::new(),
+        };
+        inner.next_id += 1;
+
+        let shared = Arc::new(Mutex::new(status.clone()));
+        inner.scans.push_back(shared.clone());
+        while inner.scans.len() > MAX_KEPT_SCANS {
+            inner.scans.pop_front();
+        }
+
+        // 遍历是阻塞的文件系统操作，放到独立线程
+        std::thread::spawn(move || {
+            let walker = Walker::new(&metadata, max_depth, time_limit_secs, shared.clone());
+            run_guarded(&shared, || walker.run(&root));
+        });
+
+        Ok(status)
+    }
+
+    pub fn get(&self, id: u64) -> Option<ScanStatus> {
+        let inner = lock(&self.inner);
+        let scan = inner.scans.iter().find(|s| lock(s).id == id)?;
+        let status = lock(scan).clone();
+        Some(status)
+    }
+}
+
+/// 加锁；持锁线程 panic 后锁被毒化，但其中的状态仍可读写，不应让后续请求也跟着 panic
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// 执行扫描；扫描过程 panic 时把任务标记为失败，否则任务会一直停留在 Running，
+/// 并且阻止之后的所有扫描
+fn run_guarded(status: &Mutex<ScanStatus>, scan: impl FnOnce()) {
+    if panic::catch_unwind(AssertUnwindSafe(scan)).is_ok() {
+        return;
+    }
+    let mut status = lock(status);
+    status.state = ScanState::Failed;
+    status.finished_at = Some(Utc::now().timestamp());
+    status.current_path = None;
+    status.error = Some("扫描线程异常退出".to_string());
+}
+
+/// 保留最大的 N 项（小顶堆）
+struct TopN {
+    heap: BinaryHeap<Reverse<(u64, PathBuf)>>,
+}
+
+impl TopN {
+    fn new() -> Self {
+        Self {
+            heap: BinaryHeap::with_capacity(TOP_ENTRIES + 1),
+        }
+    }
+
+    fn push(&mut self, size: u64, path: &Path) {
+        if self.heap.len() < TOP_ENTRIES {
+            self.heap.push(Reverse((size, path.to_path_buf())));
+        } else if self
+            .heap
+            .peek()
+            .is_some_and(|Reverse((min, _))| size > *min)
+        {
+            self.heap.pop();
+            self.heap.push(Reverse((size, path.to_path_buf())));
+        }
+    }
+
+    fn into_entries(self) -> Vec<ScanEntry> {
+        // Reverse 的升序即大小降序
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((size, path))| ScanEntry {
+                path: path.to_string_lossy().to_string(),
+                size_bytes: size,
+            })
+            .collect()
+    }
+}
+
+/// 类似 `du -x`：不跟随符号链接、不跨越文件系统，按实际占用的块计算大小
+/// 硬链接会被重复计算
+struct Walker {
+    root_device: u64,
+    max_depth: usize,
+    started: Instant,
+    deadline: Instant,
+    status: Arc<Mutex<ScanStatus>>,
+    files: u64,
+    dirs: u64,
+    bytes: u64,
+    errors: u64,
+    timed_out: bool,
+    depth_limited: bool,
+    largest_dirs: TopN,
+    largest_files: TopN,
+    last_progress: Instant,
+}
+
+impl Walker {
+    fn new(
+        root: &Metadata,
+        max_depth: usize,
+        time_limit_secs: u64,
+        status: Arc<Mutex<ScanStatus>>,
+    ) -> Self {
+        let now = Instant::now();
+        Self {
+            root_device: device_id(root),
+            max_depth,
+            started: now,
+            deadline: now + Duration::from_secs(time_limit_secs),
+            status,
+            files: 0,
+            dirs: 0,
+            bytes: 0,
+            errors: 0,
+            timed_out: false,
+            depth_limited: false,
+            largest_dirs: TopN::new(),
+            largest_files: TopN::new(),
+            last_progress: now,
+        }
+    }
+
+    fn run(mut self, root: &Path) {
+        self.walk(root, 0);
+
+        let truncated_reason = match (self.timed_out, self.depth_limited) {
+            (true, _) => Some(format!(
+                "超过时间限制 {} 秒",
+                self.deadline.duration_since(self.started).as_secs()
+            )),
+            (false, true) => Some(format!("超过最大深度 {}", self.max_depth)),
+            (false, false) => None,
+        };
+
+        let mut status = lock(&self.status);
+        status.state = ScanState::Completed;
+        status.finished_at = Some(Utc::now().timestamp());
+        status.elapsed_secs = self.started.elapsed().as_secs_f64();
+        status.files_scanned = self.files;
+        status.dirs_scanned = self.dirs;
+        status.total_bytes = self.bytes;
+        status.errors = self.errors;
+        status.current_path = None;
+        status.truncated = truncated_reason.is_some();
+        status.truncated_reason = truncated_reason;
+        status.largest_dirs = self.largest_dirs.into_entries();
+        status.largest_files = self.largest_files.into_entries();
+    }
+
+    /// 返回目录内容的总占用（不含目录自身）
+    fn walk(&mut self, dir: &Path, depth: usize) -> u64 {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => {
+                self.errors += 1;
+                return 0;
+            }
+        };
+
+        let mut total = 0;
+        for entry in entries {
+            if Instant::now() >= self.deadline {
+                self.timed_out = true;
+                break;
+            }
+            let Ok(entry) = entry else {
+                self.errors += 1;
+                continue;
+            };
+            let path = entry.path();
+            // 不跟随符号链接
+            let Ok(metadata) = fs::symlink_metadata(&path) else {
+                self.errors += 1;
+                continue;
+            };
+            // 其他文件系统的挂载点（如 /var 下挂载的 /var/lib/docker）不计入
+            if metadata.is_dir() && device_id(&metadata) != self.root_device {
+                continue;
+            }
+            let size = allocated_bytes(&metadata);
+            self.bytes += size;
+
+            if metadata.is_dir() {
+                self.dirs += 1;
+                let mut dir_total = size;
+                if depth < self.max_depth {
+                    dir_total += self.walk(&path, depth + 1);
+                } else {
+                    self.depth_limited = true;
+                }
+                self.largest_dirs.push(dir_total, &path);
+                total += dir_total;
+            } else {
+                self.files += 1;
+                self.largest_files.push(size, &path);
+                total += size;
+            }
+
+            self.report_progress(&path);
+        }
+        total
+    }
+
+    fn report_progress(&mut self, path: &Path) {
+        if self.last_progress.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        self.last_progress = Instant::now();
+
+        let mut status = lock(&self.status);
+        status.elapsed_secs = self.started.elapsed().as_secs_f64();
+        status.files_scanned = self.files;
+        status.dirs_scanned = self.dirs;
+        status.total_bytes = self.bytes;
+        status.errors = self.errors;
+        status.current_path = Some(path.to_string_lossy().to_string());
+    }
+}
+
+#[cfg(unix)]
+fn device_id(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.dev()
+}
+
+#[cfg(not(unix))]
+fn device_id(_metadata: &Metadata) -> u64 {
+    0
+}
+
+/// 实际占用的磁盘空间（稀疏文件小于 len），与 du 一致
+#[cfg(unix)]
+fn allocated_bytes(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated_bytes(metadata: &Metadata) -> u64 {
+    metadata.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::FixtureTree;
+
+    fn wait(scans: &DiskScans, id: u64) -> ScanStatus {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let status = scans.get(id).unwrap();
+            if status.state != ScanState::Running || Instant::now() > deadline {
+                return status;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn scans_tree() {
+        let tree = FixtureTree::new("disk-scan");
+        tree.write("a/one.bin", &"x".repeat(64 * 1024))
+            .write("a/b/two.txt", "hello")
+            .write("three.txt", "world");
+
+        let scans = DiskScans::new();
+        let started = scans
+            .start(&tree.root.to_string_lossy(), None, None)
+            .ok()
+            .unwrap();
+        assert_eq!(started.state, ScanState::Running);
+
+        let status = wait(&scans, started.id);
+        assert_eq!(status.state, ScanState::Completed);
+        assert_eq!(status.files_scanned, 3);
+        assert_eq!(status.dirs_scanned, 2);
+        assert!(!status.truncated);
+        assert!(status.largest_files[0].path.ends_with("one.bin"));
+    }
+
+    #[test]
+    fn rejects_relative_or_missing_paths() {
+        let scans = DiskScans::new();
+        assert!(matches!(
+            scans.start("tmp", None, None),
+            Err(StartError::InvalidPath)
+        ));
+        assert!(matches!(
+            scans.start("/nonexistent/dir", None, None),
+            Err(StartError::InvalidPath)
+        ));
+    }
+
+    #[test]
+    fn depth_limit_truncates() {
+        let tree = FixtureTree::new("disk-scan-depth");
+        tree.write("a/b/c/deep.txt", "deep");
+
+        let scans = DiskScans::new();
+        let started = scans
+            .start(&tree.root.to_string_lossy(), Some(1), None)
+            .ok()
+            .unwrap();
+        let status = wait(&scans, started.id);
+        assert!(status.truncated);
+        assert_eq!(status.files_scanned, 0);
+    }
+
+    #[test]
+    fn panic_marks_scan_failed() {
+        let scans = DiskScans::new();
+        let tree = FixtureTree::new("disk-scan-panic");
+        let started = scans
+            .start(&tree.root.to_string_lossy(), None, None)
+            .ok()
+            .unwrap();
+        wait(&scans, started.id);
+
+        // 持锁时 panic，锁被毒化
+        let shared = lock(&scans.inner).scans[0].clone();
+        run_guarded(&shared, || {
+            let mut status = shared.lock().unwrap();
+            status.state = ScanState::Running;
+            panic!("walker failed");
+        });
+
+        let status = scans.get(started.id).unwrap();
+        assert_eq!(status.state, ScanState::Failed);
+        assert!(status.error.is_some());
+        // 失败的任务不再阻止新的扫描
+        assert!(
+            scans
+                .start(&tree.root.to_string_lossy(), None, None)
+                .is_ok()
+        );
+    }
+}
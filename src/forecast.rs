@@ -0,0 +1,178 @@
+use crate::collector::DiskInfo;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+// 每个挂载点每分钟记录一个使用量点，保留最近 24 小时
+const HISTORY_INTERVAL_SECS: i64 = 60;
+const HISTORY_WINDOW_SECS: i64 = 24 * 3600;
+// 至少需要的点数和覆盖时长，太少、太短的数据不做预测
+// （刚启动时的几分钟里一次大文件写入就会得出几小时内写满的结论）
+const MIN_FORECAST_POINTS: usize = 10;
+const MIN_FORECAST_SPAN_SECS: i64 = 3600;
+// Theil-Sen 需要计算所有点对的斜率，点数超过该值时均匀抽样
+const MAX_FIT_POINTS: usize = 200;
+
+#[derive(Serialize, Clone)]
+pub struct DiskForecast {
+    // 拟合得到的增长速度（GB/天），负数表示在释放空间
+    pub fill_rate_gb_per_day: f64,
+    // 按当前速度写满还需要的时间，没有增长时为 None
+    pub hours_to_full: Option<f64>,
+    // 预计写满的时间点（Unix 秒）
+    pub estimated_full_at: Option<i64>,
+    // 参与拟合的历史点数和覆盖时长
+    pub samples: usize,
+    pub window_hours: f64,
+}
+
+/// 磁盘使用量历史：挂载点 -> (时间戳, 已用 GB)
+pub struct DiskHistory {
+    points: HashMap<String, VecDeque<(i64, f64)>>,
+}
+
+impl DiskHistory {
+    pub fn new() -> Self {
+        Self {
+            points: HashMap::new(),
+        }
+    }
+
+    /// 记录本次采样的使用量（同一挂载点一分钟内只记录一次），
+    /// 并丢弃已卸载的挂载点的历史
+    pub fn record(&mut self, now: i64, disks: &[DiskInfo]) {
+        self.points
+            .retain(|mount, _| disks.iter().any(|d| d.mount_point == *mount));
+        for disk in disks {
+            let history = self.points.entry(disk.mount_point.clone()).or_default();
+            if history
+                .back()
+                .is_some_and(|(at, _)| now - at < HISTORY_INTERVAL_SECS)
+            {
+                continue;
+            }
+            history.push_back((now, disk.used_gb));
+            while history
+                .front()
+                .is_some_and(|(at, _)| now - at > HISTORY_WINDOW_SECS)
+            {
+                history.pop_front();
+            }
+        }
+    }
+
+    /// 用历史数据预测挂载点写满的时间
+    pub fn forecast(&self, disk: &DiskInfo, now: i64) -> Option<DiskForecast> {
+        let history = self.points.get(&disk.mount_point)?;
+        if history.len() < MIN_FORECAST_POINTS {
+            return None;
+        }
+
+        let (first_at, _) = *history.front()?;
+        let (last_at, _) = *history.back()?;
+        if last_at - first_at < MIN_FORECAST_SPAN_SECS {
+            return None;
+        }
+
+        let step = history.len().div_ceil(MAX_FIT_POINTS);
+        // 横轴用小时，避免时间戳过大带来的精度问题
+        let points: Vec<(f64, f64)> = history
+            .iter()
+            .step_by(step)
+            .map(|(at, used)| ((at - first_at) as f64 / 3600.0, *used))
+            .collect();
+
+        let slope_per_hour = theil_sen_slope(&points)?;
+        let remaining_gb = (disk.total_gb - disk.used_gb).max(0.0);
+        let hours_to_full = if slope_per_hour > f64::EPSILON {
+            Some(remaining_gb / slope_per_hour)
+        } else {
+            None
+        };
+
+        Some(DiskForecast {
+            fill_rate_gb_per_day: slope_per_hour * 24.0,
+            hours_to_full,
+            estimated_full_at: hours_to_full.map(|h| now + (h * 3600.0) as i64),
+            samples: history.len(),
+            window_hours: (last_at - first_at) as f64 / 3600.0,
+        })
+    }
+}
+
+/// Theil-Sen 估计：所有点对斜率的中位数
+/// 比最小二乘更不容易被临时文件、日志轮转造成的突变带偏
+fn theil_sen_slope(points: &[(f64, f64)]) -> Option<f64> {
+    let mut slopes = Vec::with_capacity(points.len() * points.len() / 2);
+    for (i, (x1, y1)) in points.iter().enumerate() {
+        for (x2, y2) in &points[i + 1..] {
+            if (x2 - x1).abs() > f64::EPSILON {
+                slopes.push((y2 - y1) / (x2 - x1));
+            }
+        }
+    }
+    if slopes.is_empty() {
+        return None;
+    }
+
+    slopes.sort_by(|a, b| a.total_cmp(b));
+    let mid = slopes.len() / 2;
+    Some(if slopes.len() % 2 == 0 {
+        (slopes[mid - 1] + slopes[mid]) / 2.0
+    } else {
+        slopes[mid]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disk(mount_point: &str, used_gb: f64) -> DiskInfo {
+        DiskInfo::fixture(mount_point, used_gb, false)
+    }
+
+    #[test]
+    fn needs_an_hour_of_history() {
+        let mut history = DiskHistory::new();
+        // 每分钟增长 0.1 GB，30 分钟的点数已足够，但跨度不足
+        for minute in 0..=30 {
+            history.record(minute * 60, &[disk("/", 10.0 + minute as f64 * 0.1)]);
+        }
+        assert!(history.forecast(&disk("/", 13.0), 30 * 60).is_none());
+
+        for minute in 31..=60 {
+            history.record(minute * 60, &[disk("/", 10.0 + minute as f64 * 0.1)]);
+        }
+        let forecast = history.forecast(&disk("/", 16.0), 3600).unwrap();
+        assert!((forecast.fill_rate_gb_per_day - 144.0).abs() < 1e-6);
+        // 剩余 84 GB，每小时 6 GB
+        assert!((forecast.hours_to_full.unwrap() - 14.0).abs() < 1e-6);
+        assert_eq!(forecast.samples, 61);
+    }
+
+    #[test]
+    fn shrinking_usage_never_fills() {
+        let mut history = DiskHistory::new();
+        for minute in 0..=90 {
+            history.record(minute * 60, &[disk("/data", 50.0 - minute as f64 * 0.01)]);
+        }
+        let forecast = history.forecast(&disk("/data", 49.1), 90 * 60).unwrap();
+        assert!(forecast.fill_rate_gb_per_day < 0.0);
+        assert!(forecast.hours_to_full.is_none());
+    }
+
+    #[test]
+    fn drops_unmounted_history() {
+        let mut history = DiskHistory::new();
+        history.record(0, &[disk("/", 1.0), disk("/mnt/usb", 1.0)]);
+        history.record(60, &[disk("/", 1.0)]);
+        assert!(history.points.contains_key("/"));
+        assert!(!history.points.contains_key("/mnt/usb"));
+    }
+
+    #[test]
+    fn theil_sen_ignores_outlier() {
+        let points = [(0.0, 1.0), (1.0, 2.0), (2.0, 3.0), (3.0, 40.0), (4.0, 5.0)];
+        assert_eq!(theil_sen_slope(&points), Some(1.0));
+    }
+}
@@ -0,0 +1,973 @@
+use crate::pci_ids::{self, VENDOR_AMD, VENDOR_INTEL, VENDOR_NVIDIA};
+use crate::sysfs::{indexed_entries, read_string, read_u64};
+use nvml_wrapper::Nvml;
+use nvml_wrapper::bitmasks::device::ThrottleReasons;
+use nvml_wrapper::enum_wrappers::device::{
+    Clock, EccCounter, MemoryError, PcieUtilCounter, PerformanceState, TemperatureSensor,
+};
+use nvml_wrapper::enums::device::UsedGpuMemory;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+pub const DRM_SYSFS_ROOT: &str = "/sys/class/drm";
+pub const PROC_ROOT: &str = "/proc";
+
+// 重新扫描所有进程描述符、查找 DRM 客户端的间隔
+const DRM_FD_RESCAN_INTERVAL: Duration = Duration::from_secs(30);
+// PCIe 吞吐读取间隔：NVML 每次查询阻塞约 20ms，在单独线程中读取
+const PCIE_THROUGHPUT_INTERVAL: Duration = Duration::from_secs(5);
+
+// 每块 GPU 显示的进程数
+const TOP_GPU_PROCESSES: usize = 5;
+
+#[derive(Serialize, Clone)]
+pub struct GpuInfo {
+    // 按 PCI 地址排序后的序号，所有厂商统一编号
+    pub index: u32,
+    // NVML 设备序号（与 nvidia-smi 一致），非 NVIDIA 显卡为空
+    pub nvml_index: Option<u32>,
+    // /sys/class/drm/cardN 的 N，没有 DRM 设备（未加载 nvidia-drm）时为空
+    pub drm_card: Option<u32>,
+    // PCI 地址（如 0000:01:00.0），跨重启稳定，可用于与 nvidia-smi / lspci 对应
+    pub pci_bus_id: Option<String>,
+    pub vendor: String, // 厂商：NVIDIA / AMD / Intel
+    pub name: String,
+    // PCI 厂商和设备 ID，如 10de:2204
+    pub pci_id: Option<String>,
+    // 内核驱动：nvidia / nouveau / amdgpu / i915 / xe 等
+    pub driver: Option<String>,
+    pub pcie_link: Option<PcieLink>,
+    pub usage_percent: u32,
+    pub memory_total_mb: u64,
+    pub memory_used_mb: u64,
+    // GTT：GPU 可访问的系统内存（AMD 集显主要使用这部分）
+    pub gtt_total_mb: Option<u64>,
+    pub gtt_used_mb: Option<u64>,
+    pub temperature: u32,
+    pub fan_speed_percent: Option<u32>, // 风扇转速百分比
+    pub power_watts: Option<f64>,       // 当前功耗
+    // 以下仅 NVIDIA（NVML）提供
+    pub power_limit_watts: Option<f64>,    // 生效的功耗上限
+    pub performance_state: Option<String>, // 性能状态 P0（最高）- P15
+    // 当前降频原因，如 sw_power_cap / hw_thermal_slowdown，空闲时为 gpu_idle
+    pub throttle_reasons: Vec<String>,
+    // PCIe 吞吐（KB/s，NVML 约 20ms 的采样窗口），由单独线程定期读取
+    pub pcie_tx_kb_per_sec: Option<u32>,
+    pub pcie_rx_kb_per_sec: Option<u32>,
+    pub encoder_usage_percent: Option<u32>,
+    pub decoder_usage_percent: Option<u32>,
+    // 显存 ECC 错误计数，未开启 ECC 时为空
+    pub ecc_errors: Option<EccErrors>,
+    pub core_clock_mhz: Option<u32>,        // 核心频率
+    pub memory_clock_mhz: Option<u32>,      // 显存频率
+    pub top_processes: Vec<GpuProcessInfo>, // 占用显存的进程
+}
+
+/// ECC 错误计数：volatile 为本次驱动加载以来，aggregate 为显卡生命周期累计
+#[derive(Serialize, Clone)]
+pub struct EccErrors {
+    pub corrected_volatile: Option<u64>,
+    pub uncorrected_volatile: Option<u64>,
+    pub corrected_aggregate: Option<u64>,
+    pub uncorrected_aggregate: Option<u64>,
+}
+
+/// PCIe 链路状态：当前速率 / 宽度低于最大值说明插槽受限或处于节能状态
+#[derive(Serialize, Clone)]
+pub struct PcieLink {
+    pub current_speed_gts: Option<f64>,
+    pub current_width: Option<u32>,
+    pub max_speed_gts: Option<f64>,
+    pub max_width: Option<u32>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct GpuProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub memory_mb: u64,
+    // 两次采样间最忙引擎的占用率（来自 DRM fdinfo），NVIDIA 为空
+    pub usage_percent: Option<f64>,
+}
+
+/// DRM 客户端（打开 /dev/dri 设备的文件描述符）在 /proc/<pid>/fdinfo 中的统计
+#[derive(Clone, Default)]
+pub struct DrmClient {
+    pub driver: String,
+    // PCI 地址，用于与 GpuInfo::pci_bus_id 对应
+    pub pdev: String,
+    pub client_id: u64,
+    // 各引擎累计占用时间（纳秒），amdgpu / i915 提供
+    pub engine_ns: HashMap<String, u64>,
+    // 各引擎累计周期数及同期 GPU 总周期数，xe 提供
+    pub engine_cycles: HashMap<String, (u64, u64)>,
+    pub memory_bytes: u64,
+}
+
+/// NVML 序号 -> PCIe 发送 / 接收吞吐（KB/s）
+pub type PcieThroughput = HashMap<u32, (Option<u32>, Option<u32>)>;
+pub type PcieThroughputCache = Arc<RwLock<PcieThroughput>>;
+
+/// 所有进程的 DRM 客户端：(PCI 地址, 客户端 ID) -> (PID, 统计)
+/// fork 继承或 dup 的描述符共享同一客户端 ID，只计一次
+pub type DrmClientSnapshot = HashMap<(String, u64), (u32, DrmClient)>;
+
+/// 采集所有 GPU：NVML 设备，加上 DRM 下其余的 PCI 显卡，按 PCI 地址排序后统一编号
+/// 厂商按 device/vendor 中的 PCI 厂商 ID 判断；process_name 用于把 PID 解析为进程名
+/// nvml 在采样器启动时初始化一次，没有 NVIDIA 驱动时为 None；
+/// pcie_throughput 为 PCIe 吞吐线程最近一次的读数
+pub fn collect_gpus(
+    drm_root: &Path,
+    nvml: Option<&Nvml>,
+    pcie_throughput: &PcieThroughput,
+    process_name: &dyn Fn(u32) -> String,
+) -> Vec<GpuInfo> {
+    let mut gpus = nvml
+        .map(|nvml| collect_nvidia_gpus(nvml, pcie_throughput, process_name))
+        .unwrap_or_default();
+
+    for (card, path) in indexed_entries(drm_root, "card") {
+        let device = path.join("device");
+        // 只统计 PCI 显卡，simpledrm 等平台设备没有 vendor 文件
+        let Some(vendor) = read_pci_id(&device.join("vendor")) else {
+            continue;
+        };
+        let device_id = read_pci_id(&device.join("device"));
+        let pci_bus_id = drm_pci_bus_id(&path);
+        let driver = fs::read_link(device.join("driver"))
+            .ok()
+            .and_then(|target| Some(target.file_name()?.to_string_lossy().into_owned()));
+        let pcie_link = read_pcie_link(&device);
+
+        // NVIDIA 显卡加载 nvidia-drm 后也会出现在 DRM 下，按 PCI 地址对应，只补充驱动和链路信息
+        if let Some(nvidia) = gpus
+            .iter_mut()
+            .find(|g| g.pci_bus_id.is_some() && g.pci_bus_id == pci_bus_id)
+        {
+            nvidia.drm_card = Some(card as u32);
+            nvidia.driver = driver;
+            nvidia.pcie_link = pcie_link;
+            continue;
+        }
+
+        let vendor_name = pci_ids::vendor_name(vendor)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{:04x}", vendor));
+        let name = device_id
+            .and_then(|id| pci_ids::device_name(vendor, id))
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{} GPU", vendor_name));
+
+        let mut info = match vendor {
+            VENDOR_AMD => collect_amd_gpu(&path, &name),
+            VENDOR_INTEL => Some(collect_intel_gpu(&path, &name)),
+            _ => None,
+        }
+        // 没有 NVML 的 NVIDIA 显卡（nouveau）及其他厂商只报告基本信息
+        .unwrap_or_else(|| GpuInfo::basic(&vendor_name, name));
+        info.drm_card = Some(card as u32);
+        info.pci_bus_id = pci_bus_id;
+        info.pci_id = device_id.map(|id| format!("{:04x}:{:04x}", vendor, id));
+        info.driver = driver;
+        info.pcie_link = pcie_link;
+        gpus.push(info);
+    }
+
+    gpus.sort_by(|a, b| a.pci_bus_id.cmp(&b.pci_bus_id));
+    for (index, gpu) in gpus.iter_mut().enumerate() {
+        gpu.index = index as u32;
+    }
+    gpus
+}
+
+impl GpuInfo {
+    /// 只有名称的 GPU 信息，各项读数为空
+    fn basic(vendor: &str, name: String) -> Self {
+        GpuInfo {
+            index: 0,
+            nvml_index: None,
+            drm_card: None,
+            pci_bus_id: None,
+            vendor: vendor.to_string(),
+            name,
+            pci_id: None,
+            driver: None,
+            pcie_link: None,
+            usage_percent: 0,
+            memory_total_mb: 0,
+            memory_used_mb: 0,
+            gtt_total_mb: None,
+            gtt_used_mb: None,
+            temperature: 0,
+            fan_speed_percent: None,
+            power_watts: None,
+            power_limit_watts: None,
+            performance_state: None,
+            throttle_reasons: vec![],
+            pcie_tx_kb_per_sec: None,
+            pcie_rx_kb_per_sec: None,
+            encoder_usage_percent: None,
+            decoder_usage_percent: None,
+            ecc_errors: None,
+            core_clock_mhz: None,
+            memory_clock_mhz: None,
+            top_processes: vec![],
+        }
+    }
+}
+
+/// sysfs 中的 PCI ID，格式为 `0x10de`
+fn read_pci_id(path: &Path) -> Option<u16> {
+    let value = read_string(path)?;
+    u16::from_str_radix(value.trim_start_matches("0x"), 16).ok()
+}
+
+/// PCI 设备的 current_link_speed / max_link_speed（如 `16.0 GT/s PCIe`）和 *_link_width
+/// 集成显卡没有这些文件
+fn read_pcie_link(device: &Path) -> Option<PcieLink> {
+    let speed = |file: &str| {
+        read_string(device.join(file))?
+            .split_whitespace()
+            .next()?
+            .parse::<f64>()
+            .ok()
+    };
+    let width = |file: &str| read_u64(device.join(file)).map(|w| w as u32);
+
+    let link = PcieLink {
+        current_speed_gts: speed("current_link_speed"),
+        current_width: width("current_link_width"),
+        max_speed_gts: speed("max_link_speed"),
+        max_width: width("max_link_width"),
+    };
+    (link.current_speed_gts.is_some() || link.max_speed_gts.is_some()).then_some(link)
+}
+
+/// cardN/device 链接到 PCI 设备目录，目录名即 PCI 地址
+fn drm_pci_bus_id(card: &Path) -> Option<String> {
+    let device = fs::canonicalize(card.join("device")).ok()?;
+    normalize_bus_id(&device.file_name()?.to_string_lossy())
+}
+
+/// 统一 PCI 地址格式：NVML 返回 `00000000:01:00.0`，sysfs 为 `0000:01:00.0`
+fn normalize_bus_id(bus_id: &str) -> Option<String> {
+    let (domain, rest) = bus_id.split_once(':')?;
+    let domain = u32::from_str_radix(domain, 16).ok()?;
+    Some(format!("{:04x}:{}", domain, rest.to_lowercase()))
+}
+
+/// 启动 PCIe 吞吐读取线程；没有 NVML 时返回空缓存
+pub fn spawn_pcie_throughput(nvml: Option<Arc<Nvml>>) -> PcieThroughputCache {
+    let cache: PcieThroughputCache = Arc::new(RwLock::new(HashMap::new()));
+    let Some(nvml) = nvml else {
+        return cache;
+    };
+
+    let writer = cache.clone();
+    std::thread::spawn(move || {
+        loop {
+            let results: PcieThroughput = (0..nvml.device_count().unwrap_or(0))
+                .filter_map(|index| {
+                    let device = nvml.device_by_index(index).ok()?;
+                    Some((
+                        index,
+                        (
+                            device.pcie_throughput(PcieUtilCounter::Send).ok(),
+                            device.pcie_throughput(PcieUtilCounter::Receive).ok(),
+                        ),
+                    ))
+                })
+                .collect();
+            if let Ok(mut slot) = writer.write() {
+                *slot = results;
+            }
+            std::thread::sleep(PCIE_THROUGHPUT_INTERVAL);
+        }
+    });
+
+    cache
+}
+
+fn collect_nvidia_gpus(
+    nvml: &Nvml,
+    pcie_throughput: &PcieThroughput,
+    process_name: &dyn Fn(u32) -> String,
+) -> Vec<GpuInfo> {
+    let count = nvml.device_count().unwrap_or(0);
+
+    (0..count)
+        .filter_map(|index| {
+            let device = nvml.device_by_index(index).ok()?;
+            let name = device.name().unwrap_or_else(|_| "Unknown GPU".to_string());
+            let usage_percent = device.utilization_rates().map(|u| u.gpu).unwrap_or(0);
+            let memory_info = device.memory_info().ok()?;
+            let memory_total_mb = memory_info.total / 1024 / 1024;
+            let memory_used_mb = memory_info.used / 1024 / 1024;
+            let temperature = device.temperature(TemperatureSensor::Gpu).unwrap_or(0);
+
+            // 风扇转速
+            let fan_speed_percent = device.fan_speed(0).ok();
+
+            // 时钟频率
+            let core_clock_mhz = device.clock_info(Clock::Graphics).ok();
+            let memory_clock_mhz = device.clock_info(Clock::Memory).ok();
+
+            // 功耗（毫瓦）
+            let power_watts = device.power_usage().ok().map(|mw| mw as f64 / 1000.0);
+            let power_limit_watts = device
+                .enforced_power_limit()
+                .ok()
+                .map(|mw| mw as f64 / 1000.0);
+
+            // ECC 只在开启时统计，消费级显卡不支持
+            let ecc_errors = device
+                .is_ecc_enabled()
+                .is_ok_and(|state| state.currently_enabled)
+                .then(|| {
+                    let count = |error, counter| device.total_ecc_errors(error, counter).ok();
+                    EccErrors {
+                        corrected_volatile: count(MemoryError::Corrected, EccCounter::Volatile),
+                        uncorrected_volatile: count(MemoryError::Uncorrected, EccCounter::Volatile),
+                        corrected_aggregate: count(MemoryError::Corrected, EccCounter::Aggregate),
+                        uncorrected_aggregate: count(
+                            MemoryError::Uncorrected,
+                            EccCounter::Aggregate,
+                        ),
+                    }
+                });
+
+            // 占用显存的进程
+            let mut top_processes: Vec<GpuProcessInfo> = device
+                .running_graphics_processes()
+                .map(|processes| {
+                    processes
+                        .iter()
+                        .map(|p| {
+                            // 将 UsedGpuMemory 枚举转换为字节数
+                            let memory_bytes = match p.used_gpu_memory {
+                                UsedGpuMemory::Used(bytes) => bytes,
+                                UsedGpuMemory::Unavailable => 0,
+                            };
+
+                            GpuProcessInfo {
+                                pid: p.pid,
+                                name: process_name(p.pid),
+                                memory_mb: memory_bytes / 1024 / 1024,
+                                usage_percent: None,
+                            }
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            top_processes.sort_by_key(|p| std::cmp::Reverse(p.memory_mb));
+            top_processes.truncate(TOP_GPU_PROCESSES);
+
+            // pci_device_id 高 16 位为设备 ID，低 16 位为厂商 ID
+            let pci = device.pci_info().ok();
+            let (pcie_tx_kb_per_sec, pcie_rx_kb_per_sec) =
+                pcie_throughput.get(&index).copied().unwrap_or_default();
+
+            Some(GpuInfo {
+                index,
+                nvml_index: Some(index),
+                drm_card: None,
+                pci_bus_id: pci.as_ref().and_then(|pci| normalize_bus_id(&pci.bus_id)),
+                vendor: "NVIDIA".to_string(),
+                name,
+                pci_id: pci
+                    .as_ref()
+                    .map(|pci| format!("{:04x}:{:04x}", VENDOR_NVIDIA, pci.pci_device_id >> 16)),
+                driver: None,
+                pcie_link: None,
+                usage_percent,
+                memory_total_mb,
+                memory_used_mb,
+                gtt_total_mb: None,
+                gtt_used_mb: None,
+                temperature,
+                fan_speed_percent,
+                power_watts,
+                power_limit_watts,
+                performance_state: device.performance_state().ok().and_then(pstate_name),
+                throttle_reasons: device
+                    .current_throttle_reasons()
+                    .map(throttle_reason_names)
+                    .unwrap_or_default(),
+                pcie_tx_kb_per_sec,
+                pcie_rx_kb_per_sec,
+                encoder_usage_percent: device.encoder_utilization().ok().map(|u| u.utilization),
+                decoder_usage_percent: device.decoder_utilization().ok().map(|u| u.utilization),
+                ecc_errors,
+                core_clock_mhz,
+                memory_clock_mhz,
+                top_processes,
+            })
+        })
+        .collect()
+}
+
+fn pstate_name(state: PerformanceState) -> Option<String> {
+    let level = match state {
+        PerformanceState::Zero => 0,
+        PerformanceState::One => 1,
+        PerformanceState::Two => 2,
+        PerformanceState::Three => 3,
+        PerformanceState::Four => 4,
+        PerformanceState::Five => 5,
+        PerformanceState::Six => 6,
+        PerformanceState::Seven => 7,
+        PerformanceState::Eight => 8,
+        PerformanceState::Nine => 9,
+        PerformanceState::Ten => 10,
+        PerformanceState::Eleven => 11,
+        PerformanceState::Twelve => 12,
+        PerformanceState::Thirteen => 13,
+        PerformanceState::Fourteen => 14,
+        PerformanceState::Fifteen => 15,
+        PerformanceState::Unknown => return None,
+    };
+    Some(format!("P{}", level))
+}
+
+fn throttle_reason_names(reasons: ThrottleReasons) -> Vec<String> {
+    [
+        (ThrottleReasons::GPU_IDLE, "gpu_idle"),
+        (
+            ThrottleReasons::APPLICATIONS_CLOCKS_SETTING,
+            "applications_clocks",
+        ),
+        (ThrottleReasons::SW_POWER_CAP, "sw_power_cap"),
+        (ThrottleReasons::HW_SLOWDOWN, "hw_slowdown"),
+        (ThrottleReasons::SYNC_BOOST, "sync_boost"),
+        (ThrottleReasons::SW_THERMAL_SLOWDOWN, "sw_thermal_slowdown"),
+        (ThrottleReasons::HW_THERMAL_SLOWDOWN, "hw_thermal_slowdown"),
+        (
+            ThrottleReasons::HW_POWER_BRAKE_SLOWDOWN,
+            "hw_power_brake_slowdown",
+        ),
+        (ThrottleReasons::DISPLAY_CLOCK_SETTING, "display_clock"),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| reasons.contains(*flag))
+    .map(|(_, name)| name.to_string())
+    .collect()
+}
+
+/// amdgpu 驱动的 sysfs 接口（cardN/device 下）：
+/// gpu_busy_percent、mem_info_vram_*、mem_info_gtt_*、pp_dpm_sclk / pp_dpm_mclk，
+/// 温度、风扇和功耗在 device/hwmon/hwmonN 下（编号不固定，需要查找）
+fn collect_amd_gpu(card: &Path, model: &str) -> Option<GpuInfo> {
+    let device = card.join("device");
+    // mem_info_vram_total 只有 amdgpu 驱动提供，以此区分其他显卡
+    if !device.join("mem_info_vram_total").exists() {
+        return None;
+    }
+
+    // product_name 只有部分独显提供，否则用 PCI ID 对应的型号
+    let name = read_string(device.join("product_name"))
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| model.to_string());
+    let mb = |file: &str| read_u64(device.join(file)).map(|bytes| bytes / 1024 / 1024);
+
+    let hwmon = indexed_entries(&device.join("hwmon"), "hwmon")
+        .into_iter()
+        .next()
+        .map(|(_, path)| path);
+    let hwmon_value = |file: &str| hwmon.as_ref().and_then(|h| read_u64(h.join(file)));
+
+    // 风扇：优先用 PWM 占空比（0-255），否则按转速 / 最大转速估算
+    let fan_speed_percent = hwmon_value("pwm1")
+        .map(|pwm| (pwm * 100 / 255) as u32)
+        .or_else(|| {
+            let rpm = hwmon_value("fan1_input")?;
+            let max = hwmon_value("fan1_max").filter(|m| *m > 0)?;
+            Some((rpm * 100 / max).min(100) as u32)
+        });
+
+    // 功耗（微瓦）：较新内核为 power1_input，旧内核为 power1_average
+    let power_watts = hwmon_value("power1_input")
+        .or_else(|| hwmon_value("power1_average"))
+        .map(|uw| uw as f64 / 1_000_000.0);
+
+    Some(GpuInfo {
+        usage_percent: read_u64(device.join("gpu_busy_percent")).unwrap_or(0) as u32,
+        memory_total_mb: mb("mem_info_vram_total").unwrap_or(0),
+        memory_used_mb: mb("mem_info_vram_used").unwrap_or(0),
+        gtt_total_mb: mb("mem_info_gtt_total"),
+        gtt_used_mb: mb("mem_info_gtt_used"),
+        // 毫度转摄氏度（temp1 为 edge 温度）
+        temperature: hwmon_value("temp1_input").map(|t| t / 1000).unwrap_or(0) as u32,
+        fan_speed_percent,
+        power_watts,
+        core_clock_mhz: read_string(device.join("pp_dpm_sclk")).and_then(|s| current_dpm_clock(&s)),
+        memory_clock_mhz: read_string(device.join("pp_dpm_mclk"))
+            .and_then(|s| current_dpm_clock(&s)),
+        ..GpuInfo::basic("AMD", name)
+    })
+}
+
+/// 解析 pp_dpm_sclk / pp_dpm_mclk，当前档位以 `*` 标记：
+/// ```text
+/// 0: 500Mhz
+/// 1: 1800Mhz *
+/// ```
+fn current_dpm_clock(content: &str) -> Option<u32> {
+    content
+        .lines()
+        .find(|line| line.trim_end().ends_with('*'))?
+        .split_whitespace()
+        .nth(1)?
+        .to_lowercase()
+        .strip_suffix("mhz")?
+        .parse()
+        .ok()
+}
+
+/// Intel 集显没有专用显存，温度通常与 CPU 共享，这里只读取 i915 的当前频率
+fn collect_intel_gpu(card: &Path, name: &str) -> GpuInfo {
+    GpuInfo {
+        core_clock_mhz: read_u64(card.join("gt_cur_freq_mhz")).map(|mhz| mhz as u32),
+        ..GpuInfo::basic("Intel", name.to_string())
+    }
+}
+
+/// 读取各进程 DRM 客户端的 fdinfo
+/// 遍历所有进程的描述符代价较高，只每隔 DRM_FD_RESCAN_INTERVAL 扫描一次，
+/// 两次扫描之间只重新读取已知 DRM 描述符的 fdinfo（新打开 GPU 的进程最多延迟一个扫描间隔出现）
+pub struct DrmClientReader {
+    proc_root: PathBuf,
+    // 各进程指向 /dev/dri 的描述符，按 PID 升序
+    fds: Vec<(u32, Vec<OsString>)>,
+    scanned_at: Option<Instant>,
+}
+
+impl DrmClientReader {
+    pub fn new(proc_root: &Path) -> Self {
+        Self {
+            proc_root: proc_root.to_path_buf(),
+            fds: Vec::new(),
+            scanned_at: None,
+        }
+    }
+
+    pub fn read(&mut self, now: Instant) -> DrmClientSnapshot {
+        if self
+            .scanned_at
+            .is_none_or(|at| now.duration_since(at) >= DRM_FD_RESCAN_INTERVAL)
+        {
+            self.fds = scan_drm_fds(&self.proc_root);
+            self.scanned_at = Some(now);
+        }
+
+        // 共享的客户端归到 PID 较小的进程（通常是父进程）
+        let mut snapshot = DrmClientSnapshot::new();
+        for (pid, fds) in &self.fds {
+            let fdinfo = self.proc_root.join(pid.to_string()).join("fdinfo");
+            for fd in fds {
+                // 进程已退出或描述符已关闭时读取失败；编号被复用为其他文件时没有 drm-client-id
+                let Some(client) = fs::read_to_string(fdinfo.join(fd))
+                    .ok()
+                    .and_then(|content| parse_drm_fdinfo(&content))
+                else {
+                    continue;
+                };
+                snapshot
+                    .entry((client.pdev.clone(), client.client_id))
+                    .or_insert((*pid, client));
+            }
+        }
+        snapshot
+    }
+}
+
+/// 遍历 /proc/<pid>/fd，找出链接到 /dev/dri 的描述符
+fn scan_drm_fds(proc_root: &Path) -> Vec<(u32, Vec<OsString>)> {
+    let Ok(entries) = fs::read_dir(proc_root) else {
+        return Vec::new();
+    };
+    let mut pids: Vec<u32> = entries
+        .flatten()
+        .filter_map(|e| e.file_name().to_str()?.parse().ok())
+        .collect();
+    pids.sort_unstable();
+
+    pids.into_iter()
+        .filter_map(|pid| {
+            let fds: Vec<OsString> = fs::read_dir(proc_root.join(pid.to_string()).join("fd"))
+                .ok()?
+                .flatten()
+                .filter(|fd| {
+                    fs::read_link(fd.path()).is_ok_and(|target| target.starts_with("/dev/dri"))
+                })
+                .map(|fd| fd.file_name())
+                .collect();
+            (!fds.is_empty()).then_some((pid, fds))
+        })
+        .collect()
+}
+
+/// 是否有需要从 DRM fdinfo 获取进程信息的显卡（NVML 已提供进程列表的 NVIDIA 显卡除外）
+pub fn needs_drm_clients(gpus: &[GpuInfo]) -> bool {
+    gpus.iter()
+        .any(|g| g.drm_card.is_some() && g.nvml_index.is_none())
+}
+
+/// 解析 DRM fdinfo（Documentation/gpu/drm-usage-stats.rst）：
+/// ```text
+/// drm-driver:     amdgpu
+/// drm-pdev:       0000:03:00.0
+/// drm-client-id:  42
+/// drm-engine-gfx: 1234567 ns
+/// drm-memory-vram:        8192 KiB
+/// ```
+/// xe 用 `drm-cycles-<引擎>` / `drm-total-cycles-<引擎>` 代替引擎时间；
+/// 显存优先取 `drm-memory-*`，没有时取较新内核的 `drm-resident-*`
+pub fn parse_drm_fdinfo(content: &str) -> Option<DrmClient> {
+    let mut client = DrmClient::default();
+    let mut client_id = None;
+    let mut cycles: HashMap<String, u64> = HashMap::new();
+    let mut total_cycles: HashMap<String, u64> = HashMap::new();
+    let mut memory_bytes = 0;
+    let mut resident_bytes = 0;
+
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let number = || value.split_whitespace().next()?.parse::<u64>().ok();
+
+        if key == "drm-driver" {
+            client.driver = value.to_string();
+        } else if key == "drm-pdev" {
+            client.pdev = normalize_bus_id(value)?;
+        } else if key == "drm-client-id" {
+            client_id = number();
+        } else if let Some(engine) = key.strip_prefix("drm-engine-") {
+            // drm-engine-capacity-<引擎> 是引擎实例数，不是时间
+            if !engine.starts_with("capacity-") {
+                client.engine_ns.insert(engine.to_string(), number()?);
+            }
+        } else if let Some(engine) = key.strip_prefix("drm-total-cycles-") {
+            total_cycles.insert(engine.to_string(), number()?);
+        } else if let Some(engine) = key.strip_prefix("drm-cycles-") {
+            cycles.insert(engine.to_string(), number()?);
+        } else if key.starts_with("drm-memory-") {
+            memory_bytes += memory_value(value)?;
+        } else if key.starts_with("drm-resident-") {
+            resident_bytes += memory_value(value)?;
+        }
+    }
+
+    client.client_id = client_id?;
+    if client.pdev.is_empty() {
+        return None;
+    }
+    client.engine_cycles = cycles
+        .into_iter()
+        .filter_map(|(engine, value)| {
+            let total = *total_cycles.get(&engine)?;
+            Some((engine, (value, total)))
+        })
+        .collect();
+    client.memory_bytes = if memory_bytes > 0 {
+        memory_bytes
+    } else {
+        resident_bytes
+    };
+    Some(client)
+}
+
+/// fdinfo 中的内存值，单位为空（字节）、KiB 或 MiB
+fn memory_value(value: &str) -> Option<u64> {
+    let mut parts = value.split_whitespace();
+    let number: u64 = parts.next()?.parse().ok()?;
+    match parts.next() {
+        None => Some(number),
+        Some("KiB") => Some(number * 1024),
+        Some("MiB") => Some(number * 1024 * 1024),
+        Some(_) => None,
+    }
+}
+
+/// 单个进程在一块 GPU 上的用量（可能有多个客户端）
+#[derive(Default)]
+struct ProcessUsage {
+    memory_bytes: u64,
+    // 各引擎两次采样间的占用比例之和，没有上次快照时为空
+    engines: Option<HashMap<String, f64>>,
+}
+
+impl ProcessUsage {
+    fn add_busy(&mut self, client: &DrmClient, before: &DrmClient, elapsed: f64) {
+        let engines = self.engines.get_or_insert_with(HashMap::new);
+        for (engine, ns) in &client.engine_ns {
+            if let Some(old) = before.engine_ns.get(engine) {
+                let busy = ns.saturating_sub(*old) as f64 / (elapsed * 1e9);
+                *engines.entry(engine.clone()).or_default() += busy;
+            }
+        }
+        for (engine, (value, total)) in &client.engine_cycles {
+            if let Some((old_value, old_total)) = before.engine_cycles.get(engine) {
+                let total_delta = total.saturating_sub(*old_total);
+                if total_delta > 0 {
+                    let busy = value.saturating_sub(*old_value) as f64 / total_delta as f64;
+                    *engines.entry(engine.clone()).or_default() += busy;
+                }
+            }
+        }
+    }
+}
+
+/// 按 PCI 地址把 DRM 客户端归到各 GPU（已有 NVML 进程信息的除外）
+/// previous 为上次快照及间隔秒数，有上次快照时才能计算占用率
+pub fn attach_drm_processes(
+    gpus: &mut [GpuInfo],
+    previous: Option<(&DrmClientSnapshot, f64)>,
+    current: &DrmClientSnapshot,
+    process_name: &dyn Fn(u32) -> String,
+) {
+    for gpu in gpus.iter_mut() {
+        let Some(bus_id) = gpu.pci_bus_id.as_deref() else {
+            continue;
+        };
+        if !gpu.top_processes.is_empty() {
+            continue;
+        }
+
+        let mut per_process: HashMap<u32, ProcessUsage> = HashMap::new();
+        for (key, (pid, client)) in current.iter().filter(|((pdev, _), _)| pdev == bus_id) {
+            let usage = per_process.entry(*pid).or_default();
+            usage.memory_bytes += client.memory_bytes;
+
+            let before = previous.and_then(|(snapshot, elapsed)| {
+                let (before_pid, before) = snapshot.get(key)?;
+                (before_pid == pid && elapsed > 0.0).then_some((before, elapsed))
+            });
+            if let Some((before, elapsed)) = before {
+                usage.add_busy(client, before, elapsed);
+            }
+        }
+
+        let mut processes: Vec<GpuProcessInfo> = per_process
+            .into_iter()
+            .map(|(pid, usage)| GpuProcessInfo {
+                pid,
+                name: process_name(pid),
+                memory_mb: usage.memory_bytes / 1024 / 1024,
+                usage_percent: usage.engines.map(|engines| {
+                    engines
+                        .values()
+                        .fold(0.0_f64, |max, busy| max.max(*busy))
+                        .min(1.0)
+                        * 100.0
+                }),
+            })
+            .collect();
+        processes.sort_by(|a, b| {
+            b.memory_mb.cmp(&a.memory_mb).then_with(|| {
+                b.usage_percent
+                    .unwrap_or(0.0)
+                    .total_cmp(&a.usage_percent.unwrap_or(0.0))
+            })
+        });
+        processes.truncate(TOP_GPU_PROCESSES);
+        gpu.top_processes = processes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::FixtureTree;
+
+    /// 在 devices/<PCI 地址> 下创建 PCI 设备，并让 drm/cardN/device 指向它
+    fn pci_card(tree: &FixtureTree, card: u32, bus_id: &str, vendor: &str, device: &str) {
+        let pci = format!("devices/{}", bus_id);
+        tree.write(&format!("{}/vendor", pci), vendor)
+            .write(&format!("{}/device", pci), device)
+            .symlink(&format!("drm/card{}/device", card), tree.root.join(&pci));
+    }
+
+    #[test]
+    fn numbers_gpus_by_pci_address() {
+        let tree = FixtureTree::new("gpu-order");
+        pci_card(&tree, 0, "0000:03:00.0", "0x1a03\n", "0x2000\n");
+        pci_card(&tree, 1, "0000:00:02.0", "0x8086\n", "0xa780\n");
+
+        let gpus = collect_gpus(
+            &tree.root.join("drm"),
+            None,
+            &PcieThroughput::new(),
+            &|_| String::new(),
+        );
+        let summary: Vec<_> = gpus
+            .iter()
+            .map(|g| (g.index, g.drm_card, g.nvml_index, g.pci_bus_id.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, Some(1), None, Some("0000:00:02.0")),
+                (1, Some(0), None, Some("0000:03:00.0")),
+            ]
+        );
+        assert_eq!(gpus[0].name, "UHD Graphics 770");
+        assert_eq!(gpus[1].name, "ASPEED Graphics Family");
+    }
+
+    #[test]
+    fn reads_amdgpu_sysfs() {
+        let tree = FixtureTree::new("gpu-amd");
+        tree.write("card0/device/mem_info_vram_total", "17163091968\n")
+            .write("card0/device/mem_info_vram_used", "1073741824\n")
+            .write("card0/device/mem_info_gtt_total", "33554432000\n")
+            .write("card0/device/mem_info_gtt_used", "52428800\n")
+            .write("card0/device/gpu_busy_percent", "37\n")
+            .write("card0/device/pp_dpm_sclk", "0: 500Mhz\n1: 2100Mhz *\n")
+            .write("card0/device/pp_dpm_mclk", "0: 96Mhz *\n1: 1000Mhz\n")
+            // hwmon 编号由加载顺序决定，不一定是 hwmon1
+            .write("card0/device/hwmon/hwmon5/temp1_input", "54000\n")
+            .write("card0/device/hwmon/hwmon5/pwm1", "102\n")
+            .write("card0/device/hwmon/hwmon5/power1_average", "45000000\n");
+
+        let gpu =
+            collect_amd_gpu(&tree.root.join("card0"), "Radeon RX 6800/6900 (Navi 21)").unwrap();
+        assert_eq!(gpu.vendor, "AMD");
+        assert_eq!(gpu.name, "Radeon RX 6800/6900 (Navi 21)");
+        assert_eq!(gpu.usage_percent, 37);
+        assert_eq!(gpu.memory_total_mb, 16368);
+        assert_eq!(gpu.memory_used_mb, 1024);
+        assert_eq!(gpu.gtt_total_mb, Some(32000));
+        assert_eq!(gpu.gtt_used_mb, Some(50));
+        assert_eq!(gpu.temperature, 54);
+        assert_eq!(gpu.fan_speed_percent, Some(40));
+        assert_eq!(gpu.power_watts, Some(45.0));
+        assert_eq!(gpu.core_clock_mhz, Some(2100));
+        assert_eq!(gpu.memory_clock_mhz, Some(96));
+    }
+
+    #[test]
+    fn amd_card_without_vram_info_is_not_amdgpu() {
+        let tree = FixtureTree::new("gpu-radeon");
+        tree.write("card0/device/vendor", "0x1002\n");
+        assert!(collect_amd_gpu(&tree.root.join("card0"), "Radeon").is_none());
+    }
+
+    const AMDGPU_FDINFO: &str = "pos:\t0\nflags:\t02100002\nmnt_id:\t24\n\
+drm-driver:\tamdgpu\ndrm-pdev:\t0000:03:00.0\ndrm-client-id:\t42\n\
+drm-memory-vram:\t8192 KiB\ndrm-memory-gtt:\t2 MiB\ndrm-memory-cpu:\t0 KiB\n\
+drm-engine-gfx:\t1234567 ns\ndrm-engine-compute:\t0 ns\ndrm-engine-capacity-gfx:\t2\n";
+
+    #[test]
+    fn parses_amdgpu_fdinfo() {
+        let client = parse_drm_fdinfo(AMDGPU_FDINFO).unwrap();
+        assert_eq!(client.driver, "amdgpu");
+        assert_eq!(client.pdev, "0000:03:00.0");
+        assert_eq!(client.client_id, 42);
+        assert_eq!(client.memory_bytes, (8192 + 2048) * 1024);
+        assert_eq!(client.engine_ns.get("gfx"), Some(&1234567));
+        // capacity 是引擎实例数，不计入引擎时间
+        assert_eq!(client.engine_ns.len(), 2);
+        assert!(client.engine_cycles.is_empty());
+    }
+
+    #[test]
+    fn parses_xe_cycles_and_resident_memory() {
+        let content = "drm-driver:\txe\ndrm-pdev:\t0000:00:02.0\ndrm-client-id:\t7\n\
+drm-resident-system:\t4096\ndrm-resident-vram0:\t1 MiB\n\
+drm-cycles-rcs:\t500\ndrm-total-cycles-rcs:\t2000\ndrm-cycles-vcs:\t10\n";
+        let client = parse_drm_fdinfo(content).unwrap();
+        assert_eq!(client.memory_bytes, 4096 + 1024 * 1024);
+        assert_eq!(client.engine_cycles.get("rcs"), Some(&(500, 2000)));
+        // 没有对应 total-cycles 的引擎无法计算占用率
+        assert!(!client.engine_cycles.contains_key("vcs"));
+        assert!(client.engine_ns.is_empty());
+    }
+
+    #[test]
+    fn rejects_fdinfo_without_drm_client() {
+        // 普通文件的 fdinfo
+        assert!(parse_drm_fdinfo("pos:\t0\nflags:\t0100002\nmnt_id:\t24\n").is_none());
+        // 缺少 PCI 地址（平台设备）
+        assert!(parse_drm_fdinfo("drm-driver:\tv3d\ndrm-client-id:\t1\n").is_none());
+        // 未知的内存单位
+        assert!(
+            parse_drm_fdinfo(
+                "drm-pdev:\t0000:03:00.0\ndrm-client-id:\t1\ndrm-memory-vram:\t1 GiB\n"
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn computes_process_usage_between_snapshots() {
+        let client = |ns: u64| {
+            let mut client = parse_drm_fdinfo(AMDGPU_FDINFO).unwrap();
+            client.engine_ns.insert("gfx".to_string(), ns);
+            client
+        };
+        let key = ("0000:03:00.0".to_string(), 42);
+        let before = DrmClientSnapshot::from([(key.clone(), (100, client(0)))]);
+        let current = DrmClientSnapshot::from([(key, (100, client(500_000_000)))]);
+
+        let mut gpus = vec![GpuInfo {
+            pci_bus_id: Some("0000:03:00.0".to_string()),
+            ..GpuInfo::basic("AMD", "Radeon".to_string())
+        }];
+        attach_drm_processes(&mut gpus, Some((&before, 2.0)), &current, &|pid| {
+            format!("proc{}", pid)
+        });
+        let process = &gpus[0].top_processes[0];
+        assert_eq!(process.name, "proc100");
+        assert_eq!(process.memory_mb, 10);
+        assert_eq!(process.usage_percent, Some(25.0));
+    }
+
+    #[test]
+    fn rescans_process_fds_periodically() {
+        let tree = FixtureTree::new("gpu-fdinfo");
+        tree.symlink("proc/200/fd/5", "/dev/dri/renderD128")
+            .write("proc/200/fdinfo/5", AMDGPU_FDINFO)
+            .symlink("proc/200/fd/1", "/dev/null")
+            .write("proc/200/fdinfo/1", "pos:\t0\n")
+            .write("proc/self/fd/0", "");
+
+        let mut reader = DrmClientReader::new(&tree.root.join("proc"));
+        let start = Instant::now();
+        let snapshot = reader.read(start);
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[&("0000:03:00.0".to_string(), 42)].0, 200);
+
+        // 子进程共享同一客户端，归到 PID 较小的进程；扫描间隔内不会发现新描述符
+        tree.symlink("proc/100/fd/9", "/dev/dri/card0")
+            .write("proc/100/fdinfo/9", AMDGPU_FDINFO);
+        assert_eq!(
+            reader.read(start + Duration::from_secs(2))[&("0000:03:00.0".to_string(), 42)].0,
+            200
+        );
+        let snapshot = reader.read(start + DRM_FD_RESCAN_INTERVAL);
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[&("0000:03:00.0".to_string(), 42)].0, 100);
+    }
+
+    #[test]
+    fn only_drm_cards_without_nvml_need_fdinfo() {
+        let gpu = |nvml_index, drm_card| GpuInfo {
+            nvml_index,
+            drm_card,
+            ..GpuInfo::basic("X", String::new())
+        };
+        assert!(!needs_drm_clients(&[]));
+        assert!(!needs_drm_clients(&[gpu(Some(0), Some(1))]));
+        assert!(needs_drm_clients(&[
+            gpu(Some(0), Some(1)),
+            gpu(None, Some(0))
+        ]));
+    }
+}
@@ -1,24 +1,62 @@
 use axum::{
-    Router,
+    Json, Router,
+    extract::{FromRef, Path, Query, State},
+    http::StatusCode,
     routing::{get, post},
 };
-use std::{net::SocketAddr, sync::Arc};
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
 use tokio::signal;
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
-use tracing::{Level, info};
+use tracing::Level;
 use tracing_subscriber::FmtSubscriber;
-use crossterm::event::{Event, KeyCode, KeyModifiers};
+use crossterm::event::{Event, EventStream, KeyCode, KeyModifiers};
+use futures::StreamExt;
+use serde::Deserialize;
 
+mod alerts;
 mod auth;
 mod collector;
+mod command;
+mod connections;
+mod cpu;
+mod disk_scan;
+mod forecast;
+mod gpu;
+mod memory;
 mod network;
+mod oom;
+mod pci_ids;
+mod power;
+mod power_supply;
+mod probes;
+mod psi;
+mod sampler;
+mod sensors;
+mod smart;
 mod static_files;
+mod storage;
+mod storage_health;
+mod sysfs;
 mod tui;
 
-use auth::{AuthState, Claims, login};
+use auth::{AuthState, Claims, ErrorResponse, login, require_admin};
 use collector::SystemStats;
+use connections::ConnectionTable;
+use disk_scan::{DiskScans, ScanStatus, StartError};
+use sampler::LatestStats;
 use static_files::serve_static;
 
+// 路由共享状态
+#[derive(Clone, FromRef)]
+struct AppState {
+    auth: Arc<AuthState>,
+    stats: LatestStats,
+    scans: Arc<DiskScans>,
+}
+
 #[tokio::main]
 async fn main() {
     // 初始化日志（输出到 stderr，避免干扰 TUI）
@@ -46,6 +84,19 @@ async fn main() {
     let port = 8080;
     let username = std::env::var("MONITOR_USER").unwrap_or_else(|_| "user".to_string());
     let password = std::env::var("MONITOR_PASS").unwrap_or_else(|_| "user123".to_string());
+    // 管理员用户名（逗号分隔）；未设置时由 AuthState 把唯一配置的用户视为管理员
+    let admins: Option<Vec<String>> = std::env::var("MONITOR_ADMIN_USERS")
+        .ok()
+        .map(|v| v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect());
+    if admins.is_none() {
+        tracing::warn!("未设置 MONITOR_ADMIN_USERS，唯一配置的用户 {} 默认为管理员", username);
+    }
+    // JWT 签名密钥，未设置时每次启动随机生成（重启后需要重新登录）
+    let jwt_secret = std::env::var("MONITOR_JWT_SECRET")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .map(String::into_bytes)
+        .unwrap_or_else(auth::random_secret);
 
     // 获取网络接口信息
     let interfaces = network::get_network_interfaces();
@@ -61,23 +112,60 @@ async fn main() {
 
     // 绘制初始界面
     terminal.draw(|f| {
-        tui::draw_ui(f, port, &username_for_ui, &password_for_ui, &interfaces_for_ui);
+        tui::draw_ui(f, port, &username_for_ui, &password_for_ui, &interfaces_for_ui, &[]);
     }).unwrap();
 
+    // 启动后台采样
+    let stats = sampler::spawn(sampler::SamplerConfig::from_env());
+
+    // 定时刷新界面（显示最近的 OOM 事件）
+    let terminal = Arc::new(Mutex::new(terminal));
+    let tui_refresh = {
+        let terminal = terminal.clone();
+        let stats = stats.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(sampler::SAMPLE_INTERVAL);
+            loop {
+                interval.tick().await;
+                let oom_events = stats
+                    .read()
+                    .ok()
+                    .and_then(|slot| slot.as_ref().map(|s| s.oom.recent_events.clone()))
+                    .unwrap_or_default();
+                if let Ok(mut terminal) = terminal.lock() {
+                    let _ = terminal.draw(|f| {
+                        tui::draw_ui(f, port, &username_for_ui, &password_for_ui, &interfaces_for_ui, &oom_events);
+                    });
+                }
+            }
+        })
+    };
+
     // 构建服务
-    let auth_state = Arc::new(AuthState::new_with_credentials(username, password));
+    let auth_state = Arc::new(AuthState::new_with_credentials(username, password, admins, jwt_secret));
+    let app_state = AppState {
+        auth: auth_state,
+        stats,
+        scans: Arc::new(DiskScans::new()),
+    };
 
     let app = Router::new()
         .route("/api/login", post(login))
         .route("/api/stats", get(get_stats))
+        .route("/api/network/connections", get(get_connections))
+        .route("/api/disk/scan", post(start_disk_scan))
+        .route("/api/disk/scan/:id", get(get_disk_scan))
         .route("/*path", get(serve_static))
         .route("/", get(serve_static))
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
-        .with_state(auth_state);
+        .with_state(app_state);
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], port));
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    // 同时监听 IPv4 和 IPv6（终端界面会列出 IPv6 访问地址），系统禁用 IPv6 时只监听 IPv4
+    let listener = match bind_dual_stack(port) {
+        Ok(listener) => listener,
+        Err(_) => tokio::net::TcpListener::bind(SocketAddr::from(([0, 0, 0, 0], port))).await.unwrap(),
+    };
 
     // 优雅关闭
     let server = axum::serve(listener, app);
@@ -90,13 +178,131 @@ async fn main() {
     }
 
     // 绘制关闭界面
-    terminal.draw(|f| tui::draw_shutdown(f)).unwrap();
+    tui_refresh.abort();
+    if let Ok(mut terminal) = terminal.lock() {
+        terminal.draw(tui::draw_shutdown).unwrap();
+    }
     tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 }
 
-async fn get_stats(_claims: Claims) -> axum::Json<SystemStats> {
-    let stats = collector::collect_stats().await;
-    axum::Json(stats)
+/// 监听 [::]:port，并关闭 IPV6_V6ONLY 以同时接受 IPv4 连接（不依赖 net.ipv6.bindv6only 的系统设置）
+#[cfg(unix)]
+fn bind_dual_stack(port: u16) -> std::io::Result<tokio::net::TcpListener> {
+    use std::os::fd::AsRawFd;
+
+    let socket = tokio::net::TcpSocket::new_v6()?;
+    let only_v6: libc::c_int = 0;
+    // SAFETY: fd 在 socket 的生命周期内有效，选项值为 c_int
+    let ret = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_IPV6,
+            libc::IPV6_V6ONLY,
+            &only_v6 as *const libc::c_int as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if ret != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    socket.set_reuseaddr(true)?;
+    socket.bind(SocketAddr::from((std::net::Ipv6Addr::UNSPECIFIED, port)))?;
+    socket.listen(1024)
+}
+
+#[cfg(not(unix))]
+fn bind_dual_stack(port: u16) -> std::io::Result<tokio::net::TcpListener> {
+    let listener = std::net::TcpListener::bind(SocketAddr::from(([0, 0, 0, 0], port)))?;
+    listener.set_nonblocking(true)?;
+    tokio::net::TcpListener::from_std(listener)
+}
+
+async fn get_stats(
+    _claims: Claims,
+    State(stats): State<LatestStats>,
+) -> Result<Json<SystemStats>, (StatusCode, Json<ErrorResponse>)> {
+    let latest = stats.read().ok().and_then(|slot| slot.clone());
+    latest.map(Json).ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse {
+                error: "数据采集中，请稍后重试".to_string(),
+            }),
+        )
+    })
+}
+
+/// TCP/UDP 连接表：监听端口及所属进程、各状态连接数、连接最多的远端
+/// 需要遍历所有进程的 fd，按请求采集而不放在后台采样中
+async fn get_connections(
+    _claims: Claims,
+) -> Result<Json<ConnectionTable>, (StatusCode, Json<ErrorResponse>)> {
+    tokio::task::spawn_blocking(|| {
+        connections::collect_connections(std::path::Path::new(connections::PROC_ROOT))
+    })
+    .await
+    .map(Json)
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "连接表采集失败".to_string(),
+            }),
+        )
+    })
+}
+
+// 目录扫描参数
+#[derive(Deserialize)]
+struct ScanQuery {
+    path: String,
+    depth: Option<usize>,
+    timeout: Option<u64>, // 秒
+}
+
+/// 启动目录扫描（仅管理员），返回任务 ID，之后通过 GET /api/disk/scan/:id 轮询
+async fn start_disk_scan(
+    claims: Claims,
+    State(auth): State<Arc<AuthState>>,
+    State(scans): State<Arc<DiskScans>>,
+    Query(query): Query<ScanQuery>,
+) -> Result<(StatusCode, Json<ScanStatus>), (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&auth, &claims)?;
+
+    match scans.start(&query.path, query.depth, query.timeout) {
+        Ok(status) => Ok((StatusCode::ACCEPTED, Json(status))),
+        Err(StartError::InvalidPath) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "路径无效，需要存在的目录的绝对路径".to_string(),
+            }),
+        )),
+        Err(StartError::Busy) => Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "已有扫描正在进行，请稍后重试".to_string(),
+            }),
+        )),
+    }
+}
+
+/// 查询目录扫描进度和结果（仅管理员）
+async fn get_disk_scan(
+    claims: Claims,
+    State(auth): State<Arc<AuthState>>,
+    State(scans): State<Arc<DiskScans>>,
+    Path(id): Path<u64>,
+) -> Result<Json<ScanStatus>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&auth, &claims)?;
+
+    scans.get(id).map(Json).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "扫描任务不存在".to_string(),
+            }),
+        )
+    })
 }
 
 /// 处理关闭信号（支持 Raw Mode 下的 Ctrl+C 和 q 键）
@@ -119,21 +325,18 @@ async fn shutdown_signal() {
     let terminate = std::future::pending::<()>();
 
     // 新增：监听键盘事件（解决 Raw Mode 下 Ctrl+C 失效问题）
+    // 使用异步的 EventStream：阻塞的 read() 无法取消，收到 SIGTERM 后会一直卡住退出流程
     let keyboard = async {
-        loop {
-            // 等待键盘事件
-            match crossterm::event::read() {
-                Ok(Event::Key(key)) => {
-                    // 检测 Ctrl+C 或 q 键
-                    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
-                        break;
-                    }
-                    if key.code == KeyCode::Char('q') {
-                        break;
-                    }
+        let mut events = EventStream::new();
+        while let Some(Ok(event)) = events.next().await {
+            if let Event::Key(key) = event {
+                // 检测 Ctrl+C 或 q 键
+                if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                    break;
+                }
+                if key.code == KeyCode::Char('q') {
+                    break;
                 }
-                Ok(_) => continue,
-                Err(_) => break,
             }
         }
     };
@@ -0,0 +1,387 @@
+use crate::sysfs::{indexed_entries, read_string, read_u64};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+pub const MEMINFO_PATH: &str = "/proc/meminfo";
+pub const VMSTAT_PATH: &str = "/proc/vmstat";
+pub const BLOCK_SYSFS_ROOT: &str = "/sys/block";
+pub const ZSWAP_ENABLED_PATH: &str = "/sys/module/zswap/parameters/enabled";
+
+/// /proc/vmstat 的计数器快照
+pub type VmStat = HashMap<String, u64>;
+
+#[derive(Serialize, Clone)]
+pub struct MemoryDetails {
+    pub total_mb: u64,
+    pub free_mb: u64,
+    // 不换出即可分配的内存（MemAvailable）
+    pub available_mb: u64,
+    // MemTotal − MemAvailable，与 SystemStats.memory_used（GB）口径一致，仅单位不同
+    pub used_mb: u64,
+    pub buffers_mb: u64,
+    // 页缓存（Cached，不含 SwapCached）
+    pub cached_mb: u64,
+    // 共享内存 / tmpfs（Shmem）
+    pub shared_mb: u64,
+    pub slab_mb: u64,
+    pub slab_reclaimable_mb: u64,
+    // 等待写回磁盘的脏页
+    pub dirty_mb: u64,
+    pub writeback_mb: u64,
+    pub swap: SwapInfo,
+    pub huge_pages: HugePages,
+    pub zram: Vec<ZramDevice>,
+    pub zswap: Option<ZswapInfo>,
+    pub paging: PagingRates,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SwapInfo {
+    pub total_mb: u64,
+    pub used_mb: u64,
+    pub free_mb: u64,
+    // 已换入内存但仍保留在交换区的页
+    pub cached_mb: u64,
+    pub usage_percent: f64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct HugePages {
+    pub total: u64,
+    pub free: u64,
+    pub reserved: u64,
+    pub page_size_kb: u64,
+    // 透明大页（THP）占用的匿名内存
+    pub anon_huge_mb: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ZramDevice {
+    pub name: String,
+    pub disksize_mb: u64,
+    // 压缩前 / 压缩后 / 实际占用内存
+    pub orig_data_mb: u64,
+    pub compr_data_mb: u64,
+    pub mem_used_mb: u64,
+    pub compression_ratio: f64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ZswapInfo {
+    pub enabled: bool,
+    // zswap 压缩池占用的内存
+    pub pool_mb: u64,
+    // 被压缩存放的页原始大小
+    pub stored_mb: u64,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct PagingRates {
+    // 与块设备之间的页面换入 / 换出（KB/s）
+    pub page_in_kb_per_sec: f64,
+    pub page_out_kb_per_sec: f64,
+    // 与交换区之间的换入 / 换出（页/s）
+    pub swap_in_pages_per_sec: f64,
+    pub swap_out_pages_per_sec: f64,
+}
+
+/// 读取 /proc/meminfo、zram 和 zswap 信息
+/// 分页速率需要两次 vmstat 快照的差值，由调用方传入
+pub fn collect_memory_details(
+    meminfo_path: &Path,
+    block_root: &Path,
+    zswap_enabled_path: &Path,
+    paging: PagingRates,
+) -> MemoryDetails {
+    let meminfo = fs::read_to_string(meminfo_path)
+        .map(|content| parse_meminfo(&content))
+        .unwrap_or_default();
+    // meminfo 中的单位均为 kB（HugePages_* 为页数）
+    let kb = |key: &str| meminfo.get(key).copied().unwrap_or(0);
+    let mb = |key: &str| kb(key) / 1024;
+
+    let total_kb = kb("MemTotal");
+    let available_kb = kb("MemAvailable");
+
+    let swap_total_kb = kb("SwapTotal");
+    let swap_used_kb = swap_total_kb.saturating_sub(kb("SwapFree"));
+
+    // 旧内核（< 5.19）没有 Zswap 字段，只能判断是否启用
+    let zswap_enabled = read_string(zswap_enabled_path).map(|s| s == "Y");
+    let zswap = match (zswap_enabled, meminfo.get("Zswap")) {
+        (None, None) => None,
+        (enabled, _) => Some(ZswapInfo {
+            enabled: enabled.unwrap_or(true),
+            pool_mb: mb("Zswap"),
+            stored_mb: mb("Zswapped"),
+        }),
+    };
+
+    MemoryDetails {
+        total_mb: total_kb / 1024,
+        free_mb: mb("MemFree"),
+        available_mb: available_kb / 1024,
+        // 先按 kB 相减再换算，避免两次取整造成与 memory_used 的偏差
+        used_mb: total_kb.saturating_sub(available_kb) / 1024,
+        buffers_mb: mb("Buffers"),
+        cached_mb: mb("Cached"),
+        shared_mb: mb("Shmem"),
+        slab_mb: mb("Slab"),
+        slab_reclaimable_mb: mb("SReclaimable"),
+        dirty_mb: mb("Dirty"),
+        writeback_mb: mb("Writeback"),
+        swap: SwapInfo {
+            total_mb: swap_total_kb / 1024,
+            used_mb: swap_used_kb / 1024,
+            free_mb: mb("SwapFree"),
+            cached_mb: mb("SwapCached"),
+            usage_percent: if swap_total_kb > 0 {
+                swap_used_kb as f64 / swap_total_kb as f64 * 100.0
+            } else {
+                0.0
+            },
+        },
+        huge_pages: HugePages {
+            total: kb("HugePages_Total"),
+            free: kb("HugePages_Free"),
+            reserved: kb("HugePages_Rsvd"),
+            page_size_kb: kb("Hugepagesize"),
+            anon_huge_mb: mb("AnonHugePages"),
+        },
+        zram: collect_zram(block_root),
+        zswap,
+        paging,
+    }
+}
+
+/// 解析 /proc/meminfo，格式：`MemTotal:       16384000 kB`
+pub fn parse_meminfo(content: &str) -> HashMap<String, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let value = rest.split_whitespace().next()?.parse().ok()?;
+            Some((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+/// 读取 /proc/vmstat 计数器
+pub fn read_vmstat(path: &Path) -> Option<VmStat> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|content| parse_vmstat(&content))
+}
+
+/// 解析 /proc/vmstat，格式：`pgpgin 1228094`
+pub fn parse_vmstat(content: &str) -> VmStat {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(' ')?;
+            Some((key.to_string(), value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// 根据两次 vmstat 快照计算分页速率
+pub fn paging_rates(previous: &VmStat, current: &VmStat, elapsed_secs: f64) -> PagingRates {
+    if elapsed_secs <= 0.0 {
+        return PagingRates::default();
+    }
+    let rate = |key: &str| {
+        let now = current.get(key).copied().unwrap_or(0);
+        let before = previous.get(key).copied().unwrap_or(0);
+        now.saturating_sub(before) as f64 / elapsed_secs
+    };
+
+    PagingRates {
+        // pgpgin / pgpgout 以 KB 计
+        page_in_kb_per_sec: rate("pgpgin"),
+        page_out_kb_per_sec: rate("pgpgout"),
+        swap_in_pages_per_sec: rate("pswpin"),
+        swap_out_pages_per_sec: rate("pswpout"),
+    }
+}
+
+fn collect_zram(block_root: &Path) -> Vec<ZramDevice> {
+    indexed_entries(block_root, "zram")
+        .into_iter()
+        .filter_map(|(index, path)| {
+            let disksize = read_u64(path.join("disksize"))?;
+            // 未初始化的 zram 设备 disksize 为 0
+            if disksize == 0 {
+                return None;
+            }
+
+            // mm_stat：orig_data_size compr_data_size mem_used_total ...（字节）
+            let mm_stat: Vec<u64> = read_string(path.join("mm_stat"))
+                .map(|s| {
+                    s.split_whitespace()
+                        .filter_map(|v| v.parse().ok())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let field = |i: usize| mm_stat.get(i).copied().unwrap_or(0);
+            let (orig, compr, used) = (field(0), field(1), field(2));
+
+            Some(ZramDevice {
+                name: format!("zram{}", index),
+                disksize_mb: disksize / 1024 / 1024,
+                orig_data_mb: orig / 1024 / 1024,
+                compr_data_mb: compr / 1024 / 1024,
+                mem_used_mb: used / 1024 / 1024,
+                compression_ratio: if compr > 0 {
+                    orig as f64 / compr as f64
+                } else {
+                    0.0
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::FixtureTree;
+
+    const MEMINFO: &str = "MemTotal:       16384000 kB
+MemFree:         2048000 kB
+MemAvailable:    8192512 kB
+Buffers:          102400 kB
+Cached:          4096000 kB
+SwapCached:        10240 kB
+Shmem:            204800 kB
+Slab:             512000 kB
+SReclaimable:     307200 kB
+Dirty:              2048 kB
+Writeback:             0 kB
+SwapTotal:       4194304 kB
+SwapFree:        3145728 kB
+Zswap:             51200 kB
+Zswapped:         204800 kB
+AnonHugePages:    409600 kB
+HugePages_Total:      16
+HugePages_Free:        8
+HugePages_Rsvd:        2
+Hugepagesize:       2048 kB
+";
+
+    #[test]
+    fn parses_meminfo() {
+        let meminfo = parse_meminfo(MEMINFO);
+        assert_eq!(meminfo["MemTotal"], 16384000);
+        assert_eq!(meminfo["HugePages_Total"], 16);
+        assert_eq!(meminfo["Hugepagesize"], 2048);
+        assert!(!meminfo.contains_key("Hugepagesize:"));
+    }
+
+    #[test]
+    fn collects_memory_details() {
+        let tree = FixtureTree::new("memory-details");
+        tree.write("meminfo", MEMINFO)
+            .write("zswap_enabled", "Y\n")
+            .write("block/zram0/disksize", "4294967296\n")
+            .write(
+                "block/zram0/mm_stat",
+                "1073741824 268435456 314572800 0 314572800 12 0 0 0\n",
+            )
+            // 未初始化的 zram 设备
+            .write("block/zram1/disksize", "0\n")
+            .write("block/sda/size", "1000\n");
+
+        let details = collect_memory_details(
+            &tree.root.join("meminfo"),
+            &tree.root.join("block"),
+            &tree.root.join("zswap_enabled"),
+            PagingRates::default(),
+        );
+
+        assert_eq!(details.total_mb, 16000);
+        assert_eq!(details.available_mb, 8000);
+        // (16384000 - 8192512) / 1024 向下取整，而不是 16000 - 8000
+        assert_eq!(details.used_mb, 7999);
+        assert_eq!(details.cached_mb, 4000);
+        assert_eq!(details.slab_reclaimable_mb, 300);
+
+        assert_eq!(details.swap.total_mb, 4096);
+        assert_eq!(details.swap.used_mb, 1024);
+        assert_eq!(details.swap.free_mb, 3072);
+        assert_eq!(details.swap.cached_mb, 10);
+        assert!((details.swap.usage_percent - 25.0).abs() < 1e-9);
+
+        assert_eq!(details.huge_pages.total, 16);
+        assert_eq!(details.huge_pages.reserved, 2);
+        assert_eq!(details.huge_pages.page_size_kb, 2048);
+        assert_eq!(details.huge_pages.anon_huge_mb, 400);
+
+        assert_eq!(details.zram.len(), 1);
+        let zram = &details.zram[0];
+        assert_eq!(zram.name, "zram0");
+        assert_eq!(zram.disksize_mb, 4096);
+        assert_eq!(zram.orig_data_mb, 1024);
+        assert_eq!(zram.compr_data_mb, 256);
+        assert_eq!(zram.mem_used_mb, 300);
+        assert!((zram.compression_ratio - 4.0).abs() < 1e-9);
+
+        let zswap = details.zswap.expect("zswap");
+        assert!(zswap.enabled);
+        assert_eq!(zswap.pool_mb, 50);
+        assert_eq!(zswap.stored_mb, 200);
+    }
+
+    #[test]
+    fn zswap_depends_on_module_and_meminfo() {
+        let tree = FixtureTree::new("memory-zswap");
+        tree.write("meminfo", "MemTotal: 1024 kB\nMemAvailable: 512 kB\n")
+            .write("zswap_disabled", "N\n");
+        let collect = |meminfo: &str, enabled: &str| {
+            collect_memory_details(
+                &tree.root.join(meminfo),
+                &tree.root.join("block"),
+                &tree.root.join(enabled),
+                PagingRates::default(),
+            )
+        };
+
+        // 没有 zswap 模块且 meminfo 无 Zswap 字段
+        assert!(collect("meminfo", "missing").zswap.is_none());
+
+        // 旧内核：只能读到是否启用
+        let zswap = collect("meminfo", "zswap_disabled").zswap.expect("zswap");
+        assert!(!zswap.enabled);
+        assert_eq!(zswap.pool_mb, 0);
+
+        // 模块参数不可读时，meminfo 中出现 Zswap 即视为启用
+        tree.write("meminfo_zswap", "Zswap: 2048 kB\nZswapped: 8192 kB\n");
+        let zswap = collect("meminfo_zswap", "missing").zswap.expect("zswap");
+        assert!(zswap.enabled);
+        assert_eq!(zswap.pool_mb, 2);
+        assert_eq!(zswap.stored_mb, 8);
+    }
+
+    #[test]
+    fn computes_paging_rates() {
+        let previous =
+            parse_vmstat("pgpgin 1000\npgpgout 2000\npswpin 10\npswpout 20\nnr_free_pages 5\n");
+        let current =
+            parse_vmstat("pgpgin 3000\npgpgout 2500\npswpin 30\npswpout 20\nnr_free_pages 9\n");
+        assert_eq!(current["nr_free_pages"], 9);
+
+        let rates = paging_rates(&previous, &current, 2.0);
+        assert!((rates.page_in_kb_per_sec - 1000.0).abs() < 1e-9);
+        assert!((rates.page_out_kb_per_sec - 250.0).abs() < 1e-9);
+        assert!((rates.swap_in_pages_per_sec - 10.0).abs() < 1e-9);
+        assert_eq!(rates.swap_out_pages_per_sec, 0.0);
+
+        // 计数器回绕或重置时不应出现负速率
+        let rates = paging_rates(&current, &previous, 2.0);
+        assert_eq!(rates.page_in_kb_per_sec, 0.0);
+
+        let rates = paging_rates(&previous, &current, 0.0);
+        assert_eq!(rates.page_in_kb_per_sec, 0.0);
+    }
+}
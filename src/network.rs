@@ -1,4 +1,21 @@
-use std::net::{IpAddr, Ipv4Addr};
+use crate::command;
+use crate::sysfs::{read_string, read_u64};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+pub const NET_DEV_PATH: &str = "/proc/net/dev";
+pub const NET_SYSFS_ROOT: &str = "/sys/class/net";
+pub const NET_WIRELESS_PATH: &str = "/proc/net/wireless";
+
+// `iw dev <if> link` 的刷新间隔和超时：SSID、速率变化不频繁，不必每次采样都启动子进程，
+// 驱动卡住时也不能拖慢采样
+const IW_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+const IW_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// 网络接口类型
 #[derive(Debug, Clone)]
@@ -10,53 +27,74 @@ pub enum InterfaceType {
     Other,    // 其他
 }
 
-/// 网络接口信息
+/// 网络接口上的一个访问地址
 #[derive(Debug, Clone)]
 pub struct NetworkInterface {
     pub name: String,
+    // IPv4 或 IPv6 地址
     pub ip: String,
     pub interface_type: InterfaceType,
+    // 链路状态、速率、MTU、MAC 地址和链路切换次数
+    pub link: LinkInfo,
+}
+
+impl NetworkInterface {
+    pub fn is_ipv6(&self) -> bool {
+        self.ip.contains(':')
+    }
+
+    /// 访问 URL，IPv6 地址需要加方括号
+    pub fn url(&self, port: u16) -> String {
+        if self.is_ipv6() {
+            format!("http://[{}]:{}", self.ip, port)
+        } else {
+            format!("http://{}:{}", self.ip, port)
+        }
+    }
 }
 
-/// 获取所有可用的网络接口，按优先级排序（WiFi > 以太网 > 其他）
+/// 获取所有网络接口的 IPv4 / IPv6 地址，按优先级排序（WiFi > 以太网 > 其他，同一接口 IPv4 在前）
+/// 跳过回环、虚拟网卡和 IPv6 链路本地地址（fe80::，访问时需要指定网卡）
 pub fn get_network_interfaces() -> Vec<NetworkInterface> {
-    let mut interfaces = Vec::new();
-
-    // 使用 local_ip_address 的正确 API
-    match local_ip_address::list_afinet_netifas() {
-        Ok(ifas) => {
-            for (name, addr) in ifas {
-                if let IpAddr::V4(ip) = addr {
-                    let ip_str = ip.to_string();
-                    let interface_type = classify_interface(&name, &ip);
-
-                    // 跳过回环和虚拟网卡
-                    if matches!(
-                        interface_type,
-                        InterfaceType::Loopback | InterfaceType::Virtual
-                    ) {
-                        continue;
-                    }
-
-                    interfaces.push(NetworkInterface {
-                        name: name.to_string(),
-                        ip: ip_str,
-                        interface_type,
-                    });
-                }
-            }
+    let net_root = Path::new(NET_SYSFS_ROOT);
+    let mut interfaces: Vec<NetworkInterface> = Vec::new();
+
+    for (name, addresses) in interface_addresses() {
+        let usable: Vec<IpAddr> = addresses
+            .iter()
+            .filter(|a| a.scope == "global")
+            .filter_map(|a| a.address.parse().ok())
+            .collect();
+        if usable.is_empty() {
+            continue;
         }
-        Err(e) => {
-            eprintln!("获取网络接口失败: {}", e);
+
+        let link = read_link_info(net_root, &name);
+        for ip in usable {
+            let interface_type = classify_interface(&name, &ip);
+            if matches!(
+                interface_type,
+                InterfaceType::Loopback | InterfaceType::Virtual
+            ) {
+                continue;
+            }
+            interfaces.push(NetworkInterface {
+                name: name.clone(),
+                ip: ip.to_string(),
+                interface_type,
+                link: link.clone(),
+            });
         }
     }
 
-    // 按优先级排序：WiFi > 以太网 > 其他
-    interfaces.sort_by_key(|i| match i.interface_type {
-        InterfaceType::WiFi => 0,
-        InterfaceType::Ethernet => 1,
-        InterfaceType::Other => 2,
-        _ => 3,
+    interfaces.sort_by_key(|i| {
+        let priority = match i.interface_type {
+            InterfaceType::WiFi => 0,
+            InterfaceType::Ethernet => 1,
+            InterfaceType::Other => 2,
+            _ => 3,
+        };
+        (priority, i.name.clone(), i.is_ipv6())
     });
 
     // 去重（同一 IP 只保留一次）
@@ -66,39 +104,8 @@ pub fn get_network_interfaces() -> Vec<NetworkInterface> {
     interfaces
 }
 
-/// 获取局域网 IP（优先 WiFi）
-pub fn get_local_ips() -> Vec<String> {
-    let interfaces = get_network_interfaces();
-
-    if interfaces.is_empty() {
-        return vec!["0.0.0.0".to_string()];
-    }
-
-    // 优先返回 WiFi，其次是其他局域网 IP
-    let wifi_ips: Vec<String> = interfaces
-        .iter()
-        .filter(|i| matches!(i.interface_type, InterfaceType::WiFi))
-        .map(|i| i.ip.clone())
-        .collect();
-
-    if !wifi_ips.is_empty() {
-        return wifi_ips;
-    }
-
-    // 如果没有 WiFi，返回所有非虚拟网卡的 IP
-    interfaces.iter().map(|i| i.ip.clone()).collect()
-}
-
-/// 获取 WiFi IP（如果有）
-pub fn get_wifi_ip() -> Option<String> {
-    get_network_interfaces()
-        .into_iter()
-        .find(|i| matches!(i.interface_type, InterfaceType::WiFi))
-        .map(|i| i.ip)
-}
-
 /// 判断接口类型
-fn classify_interface(name: &str, ip: &Ipv4Addr) -> InterfaceType {
+fn classify_interface(name: &str, ip: &IpAddr) -> InterfaceType {
     let name_lower = name.to_lowercase();
 
     // 1. 检查回环
@@ -128,12 +135,14 @@ fn classify_interface(name: &str, ip: &Ipv4Addr) -> InterfaceType {
     }
 
     // 4. 检查 IP 段（Docker 默认网段）
-    let octets = ip.octets();
-    if octets[0] == 172 && (octets[1] == 17 || octets[1] == 18) {
-        return InterfaceType::Virtual; // Docker
-    }
-    if octets[0] == 192 && octets[1] == 168 && octets[2] == 56 {
-        return InterfaceType::Virtual; // VirtualBox
+    if let IpAddr::V4(ip) = ip {
+        let octets = ip.octets();
+        if octets[0] == 172 && (octets[1] == 17 || octets[1] == 18) {
+            return InterfaceType::Virtual; // Docker
+        }
+        if octets[0] == 192 && octets[1] == 168 && octets[2] == 56 {
+            return InterfaceType::Virtual; // VirtualBox
+        }
     }
 
     // 5. 检查以太网（常见命名）
@@ -148,7 +157,13 @@ fn classify_interface(name: &str, ip: &Ipv4Addr) -> InterfaceType {
 }
 
 /// 判断是否为局域网 IP
+/// IPv6 没有 NAT，除了唯一本地地址（fc00::/7），全局地址在局域网内同样可以直接访问
 pub fn is_lan_ip(ip: &str) -> bool {
+    if let Ok(IpAddr::V6(ip)) = ip.parse::<IpAddr>() {
+        let first = ip.segments()[0];
+        // fc00::/7 唯一本地地址，2000::/3 全局单播地址
+        return first & 0xfe00 == 0xfc00 || first & 0xe000 == 0x2000;
+    }
     if let Ok(IpAddr::V4(ip)) = ip.parse() {
         let octets = ip.octets();
         // 10.x.x.x
@@ -167,17 +182,489 @@ pub fn is_lan_ip(ip: &str) -> bool {
     false
 }
 
-/// 打印网络诊断信息（调试用）
-pub fn print_network_debug() {
-    println!("网络接口诊断：");
-    for iface in get_network_interfaces() {
-        let type_str = match iface.interface_type {
-            InterfaceType::WiFi => "WiFi",
-            InterfaceType::Ethernet => "以太网",
-            InterfaceType::Virtual => "虚拟",
-            InterfaceType::Loopback => "回环",
-            InterfaceType::Other => "其他",
+/// 单个接口的累计计数器（/proc/net/dev 的一行）
+#[derive(Clone)]
+pub struct NetCounters {
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_errors: u64,
+    pub tx_dropped: u64,
+}
+
+/// 接口名 -> 计数器
+pub type NetDevSnapshot = HashMap<String, NetCounters>;
+
+/// 读取 /proc/net/dev
+pub fn read_net_dev(path: &Path) -> Option<NetDevSnapshot> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|content| parse_net_dev(&content))
+}
+
+/// 解析 /proc/net/dev，前两行为表头，之后每行：
+/// `  eth0: rx_bytes packets errs drop fifo frame compressed multicast tx_bytes packets errs drop ...`
+pub fn parse_net_dev(content: &str) -> NetDevSnapshot {
+    content
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (name, rest) = line.split_once(':')?;
+            let fields: Vec<u64> = rest
+                .split_whitespace()
+                .map(|v| v.parse().unwrap_or(0))
+                .collect();
+            if fields.len() < 16 {
+                return None;
+            }
+            Some((
+                name.trim().to_string(),
+                NetCounters {
+                    rx_bytes: fields[0],
+                    rx_packets: fields[1],
+                    rx_errors: fields[2],
+                    rx_dropped: fields[3],
+                    tx_bytes: fields[8],
+                    tx_packets: fields[9],
+                    tx_errors: fields[10],
+                    tx_dropped: fields[11],
+                },
+            ))
+        })
+        .collect()
+}
+
+/// 没有对应物理设备（/sys/class/net/<if>/device）的接口视为虚拟接口：
+/// lo、docker0、veth*、网桥、隧道等
+pub fn is_virtual_interface(net_root: &Path, name: &str) -> bool {
+    !net_root.join(name).join("device").exists()
+}
+
+/// 接口上配置的一个地址
+#[derive(Serialize, Clone)]
+pub struct InterfaceAddress {
+    pub address: String,
+    pub prefix_len: u8,
+    // ipv4 / ipv6
+    pub family: String,
+    // host（回环）/ link（链路本地）/ global
+    pub scope: String,
+}
+
+impl InterfaceAddress {
+    fn new(ip: IpAddr, prefix_len: u8) -> Self {
+        let scope = match ip {
+            IpAddr::V4(v4) if v4.is_loopback() => "host",
+            IpAddr::V4(v4) if v4.is_link_local() => "link",
+            IpAddr::V6(v6) if v6.is_loopback() => "host",
+            // fe80::/10
+            IpAddr::V6(v6) if v6.segments()[0] & 0xffc0 == 0xfe80 => "link",
+            _ => "global",
+        };
+        Self {
+            address: ip.to_string(),
+            prefix_len,
+            family: if ip.is_ipv4() { "ipv4" } else { "ipv6" }.to_string(),
+            scope: scope.to_string(),
+        }
+    }
+}
+
+/// 通过 getifaddrs 获取所有接口的 IPv4 / IPv6 地址及前缀长度
+#[cfg(unix)]
+pub fn interface_addresses() -> HashMap<String, Vec<InterfaceAddress>> {
+    use std::ffi::CStr;
+
+    let mut result: HashMap<String, Vec<InterfaceAddress>> = HashMap::new();
+    let mut ifap: *mut libc::ifaddrs = std::ptr::null_mut();
+    // SAFETY: 成功时 ifap 指向由 libc 分配的链表，最后用 freeifaddrs 释放
+    if unsafe { libc::getifaddrs(&mut ifap) } != 0 {
+        return result;
+    }
+
+    let mut cursor = ifap;
+    while !cursor.is_null() {
+        // SAFETY: cursor 是链表中的有效节点，地址结构的实际类型由 sa_family 决定
+        let ifa = unsafe { &*cursor };
+        cursor = ifa.ifa_next;
+        if ifa.ifa_addr.is_null() {
+            continue;
+        }
+
+        let name = unsafe { CStr::from_ptr(ifa.ifa_name) }
+            .to_string_lossy()
+            .to_string();
+        let entry = match i32::from(unsafe { (*ifa.ifa_addr).sa_family }) {
+            libc::AF_INET => {
+                let addr = unsafe { &*(ifa.ifa_addr as *const libc::sockaddr_in) };
+                let ip = Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr));
+                let prefix_len = if ifa.ifa_netmask.is_null() {
+                    32
+                } else {
+                    let mask = unsafe { &*(ifa.ifa_netmask as *const libc::sockaddr_in) };
+                    mask.sin_addr.s_addr.count_ones() as u8
+                };
+                InterfaceAddress::new(IpAddr::V4(ip), prefix_len)
+            }
+            libc::AF_INET6 => {
+                let addr = unsafe { &*(ifa.ifa_addr as *const libc::sockaddr_in6) };
+                let ip = Ipv6Addr::from(addr.sin6_addr.s6_addr);
+                let prefix_len = if ifa.ifa_netmask.is_null() {
+                    128
+                } else {
+                    let mask = unsafe { &*(ifa.ifa_netmask as *const libc::sockaddr_in6) };
+                    mask.sin6_addr
+                        .s6_addr
+                        .iter()
+                        .map(|b| b.count_ones() as u8)
+                        .sum()
+                };
+                InterfaceAddress::new(IpAddr::V6(ip), prefix_len)
+            }
+            _ => continue,
         };
-        println!("  {} [{}] -> {}", iface.name, type_str, iface.ip);
+        result.entry(name).or_default().push(entry);
+    }
+
+    unsafe { libc::freeifaddrs(ifap) };
+    result
+}
+
+#[cfg(not(unix))]
+pub fn interface_addresses() -> HashMap<String, Vec<InterfaceAddress>> {
+    HashMap::new()
+}
+
+/// /sys/class/net/<if> 下的链路信息
+#[derive(Debug, Clone)]
+pub struct LinkInfo {
+    // up / down / dormant / unknown（部分虚拟接口始终为 unknown）
+    pub operstate: Option<String>,
+    // 协商速率，未连接或虚拟接口为 None
+    pub speed_mbps: Option<u64>,
+    // full / half
+    pub duplex: Option<String>,
+    pub mtu: Option<u64>,
+    pub mac_address: Option<String>,
+    // 链路断开 / 恢复的累计次数，频繁增长说明网线或交换机端口不稳定
+    pub carrier_changes: Option<u64>,
+}
+
+pub fn read_link_info(net_root: &Path, name: &str) -> LinkInfo {
+    let dir = net_root.join(name);
+    // 接口 down 时读取 speed / duplex 会返回 EINVAL，未知速率为 -1
+    let speed_mbps = read_string(dir.join("speed"))
+        .and_then(|s| s.parse::<i64>().ok())
+        .filter(|s| *s > 0)
+        .map(|s| s as u64);
+
+    LinkInfo {
+        operstate: read_string(dir.join("operstate")),
+        speed_mbps,
+        duplex: read_string(dir.join("duplex")).filter(|d| d != "unknown"),
+        mtu: read_u64(dir.join("mtu")),
+        mac_address: read_string(dir.join("address"))
+            .filter(|a| !a.is_empty() && a != "00:00:00:00:00:00"),
+        carrier_changes: read_u64(dir.join("carrier_changes")),
+    }
+}
+
+/// 无线网卡的信号和连接信息
+#[derive(Serialize, Clone)]
+pub struct WirelessInfo {
+    // 链路质量（驱动定义的刻度，iwlwifi 等最大为 70）
+    pub link_quality: f64,
+    pub signal_dbm: Option<f64>,
+    pub noise_dbm: Option<f64>,
+    // 以下通过 `iw dev <if> link` 获取（每 30 秒刷新），未安装 iw 或未连接时为 None
+    pub ssid: Option<String>,
+    pub tx_bitrate_mbps: Option<f64>,
+    pub frequency_mhz: Option<u32>,
+}
+
+/// 读取 /proc/net/wireless，接口名 -> 信号信息
+pub fn read_wireless(path: &Path) -> HashMap<String, WirelessInfo> {
+    fs::read_to_string(path)
+        .map(|content| parse_wireless(&content))
+        .unwrap_or_default()
+}
+
+/// 解析 /proc/net/wireless，前两行为表头，之后每行：
+/// `wlan0: 0000   54.  -56.  -256        0      0      0      0      0        0`
+/// 依次为状态、链路质量、信号、噪声；数值带小数点，-256 表示驱动未提供
+pub fn parse_wireless(content: &str) -> HashMap<String, WirelessInfo> {
+    content
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (name, rest) = line.split_once(':')?;
+            let fields: Vec<f64> = rest
+                .split_whitespace()
+                .skip(1)
+                .take(3)
+                .filter_map(|v| v.trim_end_matches('.').parse().ok())
+                .collect();
+            let [link_quality, level, noise] = fields[..] else {
+                return None;
+            };
+            // 旧驱动以 0-255 的无符号值表示 dBm（值 - 256）
+            let dbm = |value: f64| match value {
+                v if v == -256.0 || v == 0.0 => None,
+                v if v > 0.0 => Some(v - 256.0),
+                v => Some(v),
+            };
+            Some((
+                name.trim().to_string(),
+                WirelessInfo {
+                    link_quality,
+                    signal_dbm: dbm(level),
+                    noise_dbm: dbm(noise),
+                    ssid: None,
+                    tx_bitrate_mbps: None,
+                    frequency_mhz: None,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// `iw dev <if> link` 的结果，未连接时各项为 None
+#[derive(Clone, Default)]
+pub struct IwLink {
+    pub ssid: Option<String>,
+    pub tx_bitrate_mbps: Option<f64>,
+    pub frequency_mhz: Option<u32>,
+}
+
+/// 按接口缓存 `iw` 的查询结果，每个接口每 30 秒最多查询一次
+pub struct WirelessLinkCache {
+    entries: HashMap<String, (Instant, IwLink)>,
+    query: fn(&str) -> Option<String>,
+}
+
+impl WirelessLinkCache {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            query: run_iw_link,
+        }
+    }
+
+    /// 用缓存的（或到期刷新后的）SSID、发送速率和频率补充信号信息
+    pub fn fill(&mut self, name: &str, info: &mut WirelessInfo, now: Instant) {
+        let expired = self
+            .entries
+            .get(name)
+            .is_none_or(|(at, _)| now.duration_since(*at) >= IW_REFRESH_INTERVAL);
+        if expired {
+            // 未安装 iw、超时等失败情况同样缓存，避免每次采样重试
+            let link = (self.query)(name)
+                .map(|output| parse_iw_link(&output))
+                .unwrap_or_default();
+            self.entries.insert(name.to_string(), (now, link));
+        }
+
+        let (_, link) = &self.entries[name];
+        info.ssid = link.ssid.clone();
+        info.tx_bitrate_mbps = link.tx_bitrate_mbps;
+        info.frequency_mhz = link.frequency_mhz;
+    }
+
+    /// 丢弃已不存在的接口
+    pub fn retain(&mut self, present: impl Fn(&str) -> bool) {
+        self.entries.retain(|name, _| present(name));
+    }
+}
+
+fn run_iw_link(name: &str) -> Option<String> {
+    let output =
+        command::output_with_timeout(Command::new("iw").args(["dev", name, "link"]), IW_TIMEOUT)?;
+    Some(String::from_utf8_lossy(&output).into_owned())
+}
+
+/// 解析 `iw dev <if> link` 的输出：
+/// ```text
+/// Connected to 11:22:33:44:55:66 (on wlan0)
+///         SSID: home
+///         freq: 5180
+///         tx bitrate: 866.7 MBit/s VHT-MCS 9 80MHz short GI VHT-NSS 2
+/// ```
+pub fn parse_iw_link(output: &str) -> IwLink {
+    let mut link = IwLink::default();
+    for line in output.lines() {
+        let Some((key, value)) = line.trim().split_once(": ") else {
+            continue;
+        };
+        match key {
+            "SSID" => link.ssid = Some(value.to_string()),
+            // 新版 iw 输出 `5180.0`
+            "freq" => {
+                link.frequency_mhz = value.parse::<f64>().ok().map(|f| f as u32);
+            }
+            "tx bitrate" => {
+                link.tx_bitrate_mbps = value.split_whitespace().next().and_then(|v| v.parse().ok());
+            }
+            _ => {}
+        }
+    }
+    link
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::FixtureTree;
+
+    const NET_DEV: &str = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 91674189   24755    0    0    0     0          0         0 91674189   24755    0    0    0     0       0          0
+  eth0:1234567890 987654    3   12    0     0          0       100 555666777  444333    1    2    0     0       0          0
+wlp2s0:  1000      10    0    0    0     0          0         0     2000      20    0    0    0     0       0          0
+";
+
+    #[test]
+    fn parses_net_dev() {
+        let snapshot = parse_net_dev(NET_DEV);
+        assert_eq!(snapshot.len(), 3);
+        let eth0 = &snapshot["eth0"];
+        assert_eq!(eth0.rx_bytes, 1_234_567_890);
+        assert_eq!(eth0.rx_packets, 987_654);
+        assert_eq!(eth0.rx_errors, 3);
+        assert_eq!(eth0.rx_dropped, 12);
+        assert_eq!(eth0.tx_bytes, 555_666_777);
+        assert_eq!(eth0.tx_packets, 444_333);
+        assert_eq!(eth0.tx_errors, 1);
+        assert_eq!(eth0.tx_dropped, 2);
+        // 接口名与数字之间没有空格
+        assert_eq!(snapshot["wlp2s0"].tx_bytes, 2000);
+    }
+
+    #[test]
+    fn skips_short_lines() {
+        let snapshot = parse_net_dev("header\nheader\n  eth0: 1 2 3\n");
+        assert!(snapshot.is_empty());
+    }
+
+    #[test]
+    fn parses_proc_net_wireless() {
+        let wireless = parse_wireless(
+            "\
+Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE
+ face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22
+wlp2s0: 0000   54.  -56.  -256        0      0      0      0      0        0
+ wlan1: 0000   30.  200.  160.        0      0      0      0      0        0
+",
+        );
+        let wlp = &wireless["wlp2s0"];
+        assert_eq!(wlp.link_quality, 54.0);
+        assert_eq!(wlp.signal_dbm, Some(-56.0));
+        assert_eq!(wlp.noise_dbm, None);
+        // 旧驱动的无符号 dBm
+        let wlan1 = &wireless["wlan1"];
+        assert_eq!(wlan1.signal_dbm, Some(-56.0));
+        assert_eq!(wlan1.noise_dbm, Some(-96.0));
+    }
+
+    #[test]
+    fn parses_iw_link() {
+        let link = parse_iw_link(
+            "\
+Connected to 11:22:33:44:55:66 (on wlp2s0)
+\tSSID: home network
+\tfreq: 5180.0
+\tRX: 123456 bytes (789 packets)
+\tsignal: -56 dBm
+\ttx bitrate: 866.7 MBit/s VHT-MCS 9 80MHz short GI VHT-NSS 2
+",
+        );
+        assert_eq!(link.ssid.as_deref(), Some("home network"));
+        assert_eq!(link.frequency_mhz, Some(5180));
+        assert_eq!(link.tx_bitrate_mbps, Some(866.7));
+
+        let link = parse_iw_link("Not connected.\n");
+        assert!(link.ssid.is_none() && link.tx_bitrate_mbps.is_none());
+    }
+
+    #[test]
+    fn wireless_link_is_cached() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        let mut cache = WirelessLinkCache::new();
+        cache.query = |_| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            Some("\tSSID: office\n\ttx bitrate: 144.4 MBit/s\n".to_string())
+        };
+        let mut info = parse_wireless("h\nh\nwlan0: 0000 40. -60. -256\n")
+            .remove("wlan0")
+            .unwrap();
+
+        let start = Instant::now();
+        cache.fill("wlan0", &mut info, start);
+        cache.fill("wlan0", &mut info, start + Duration::from_secs(2));
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+        assert_eq!(info.ssid.as_deref(), Some("office"));
+        assert_eq!(info.tx_bitrate_mbps, Some(144.4));
+
+        cache.fill("wlan0", &mut info, start + IW_REFRESH_INTERVAL);
+        assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+
+        cache.retain(|name| name != "wlan0");
+        assert!(cache.entries.is_empty());
+    }
+
+    #[test]
+    fn lan_addresses() {
+        assert!(is_lan_ip("192.168.1.10"));
+        assert!(is_lan_ip("10.0.0.1"));
+        assert!(is_lan_ip("172.20.0.1"));
+        assert!(!is_lan_ip("8.8.8.8"));
+        assert!(is_lan_ip("fd12:3456:789a::1"));
+        assert!(is_lan_ip("2001:db8::10"));
+        assert!(!is_lan_ip("fe80::1"));
+        assert!(!is_lan_ip("::1"));
+    }
+
+    #[test]
+    fn classifies_interfaces() {
+        let v4 = |s: &str| IpAddr::V4(s.parse().unwrap());
+        assert!(matches!(
+            classify_interface("wlp2s0", &v4("192.168.1.5")),
+            InterfaceType::WiFi
+        ));
+        assert!(matches!(
+            classify_interface("enp3s0", &"2001:db8::5".parse().unwrap()),
+            InterfaceType::Ethernet
+        ));
+        assert!(matches!(
+            classify_interface("br0", &v4("172.17.0.1")),
+            InterfaceType::Virtual
+        ));
+    }
+
+    #[test]
+    fn interface_url_brackets_ipv6() {
+        let iface = |ip: &str| NetworkInterface {
+            name: "eth0".to_string(),
+            ip: ip.to_string(),
+            interface_type: InterfaceType::Ethernet,
+            link: read_link_info(Path::new("/nonexistent"), "eth0"),
+        };
+        assert_eq!(iface("2001:db8::5").url(8080), "http://[2001:db8::5]:8080");
+        assert_eq!(iface("192.168.1.5").url(8080), "http://192.168.1.5:8080");
+    }
+
+    #[test]
+    fn virtual_interfaces_have_no_device() {
+        let tree = FixtureTree::new("net-virtual");
+        tree.write("eth0/device/vendor", "0x8086")
+            .write("docker0/operstate", "up");
+        assert!(!is_virtual_interface(&tree.root, "eth0"));
+        assert!(is_virtual_interface(&tree.root, "docker0"));
+        assert!(is_virtual_interface(&tree.root, "lo"));
     }
 }
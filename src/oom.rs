@@ -0,0 +1,438 @@
+use crate::memory::VmStat;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+pub const PROC_ROOT: &str = "/proc";
+
+// 保留的最近 OOM 事件数量
+const MAX_OOM_EVENTS: usize = 50;
+// cgroup 树的最大扫描深度（避免每次采样遍历过深的层级）
+const CGROUP_SCAN_DEPTH: usize = 5;
+
+#[derive(Serialize, Clone)]
+pub struct OomEvent {
+    // 检测到事件的时间（Unix 秒）
+    pub timestamp: i64,
+    // 触发 OOM 的 cgroup（相对 cgroup 根目录），全局 OOM 时为 None
+    pub cgroup: Option<String>,
+    // 被杀进程：该 cgroup 中上次采样存在、本次消失且内存占用最大的进程
+    // （OOM killer 按内存占用选择目标）；全局 OOM 或找不到同一 cgroup 的进程时为 None
+    pub pid: Option<u32>,
+    pub process_name: Option<String>,
+    pub process_memory_mb: Option<f64>,
+    // 本次采样间隔内的击杀次数
+    pub kills: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct OomStats {
+    // 开机以来的 OOM 击杀总数（/proc/vmstat oom_kill）
+    pub total_kills: Option<u64>,
+    // 最近的事件，最新的在前
+    pub recent_events: Vec<OomEvent>,
+}
+
+/// 进程快照：pid -> (名称, 内存 MB)
+pub type ProcessSnapshot = HashMap<u32, (String, f64)>;
+
+/// 上次采样的进程及其所属的内存 cgroup
+struct TrackedProcess {
+    name: String,
+    memory_mb: f64,
+    // 与 scan_cgroups 的 cgroup 名称格式相同，读取失败时为 None
+    cgroup: Option<String>,
+}
+
+/// OOM 跟踪器：比较两次采样间的 oom_kill 计数
+pub struct OomTracker {
+    initialized: bool,
+    last_global: Option<u64>,
+    last_cgroups: HashMap<String, u64>,
+    last_processes: HashMap<u32, TrackedProcess>,
+    events: VecDeque<OomEvent>,
+}
+
+impl OomTracker {
+    pub fn new() -> Self {
+        Self {
+            initialized: false,
+            last_global: None,
+            last_cgroups: HashMap::new(),
+            last_processes: HashMap::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    /// 用本次采样的计数器更新事件列表
+    /// 第一次调用只记录基准值，开机以来已有的 OOM 不会产生事件
+    pub fn update(
+        &mut self,
+        vmstat: Option<&VmStat>,
+        cgroup_root: &Path,
+        proc_root: &Path,
+        processes: ProcessSnapshot,
+    ) -> OomStats {
+        let global = vmstat.and_then(|v| v.get("oom_kill").copied());
+        // cgroup 内的 OOM 同样计入全局 oom_kill，计数没有增长就不必遍历 cgroup 树；
+        // 旧内核（4.13 之前）没有该计数，只能每次都扫描
+        let rescan = !self.initialized
+            || global.is_none()
+            || global
+                .zip(self.last_global)
+                .is_some_and(|(now, before)| now > before);
+        let cgroups = if rescan {
+            let mut cgroups = HashMap::new();
+            scan_cgroups(cgroup_root, cgroup_root, 0, &mut cgroups);
+            cgroups
+        } else {
+            self.last_cgroups.clone()
+        };
+
+        let mut new_events = Vec::new();
+        let now = Utc::now().timestamp();
+
+        if self.initialized {
+            for (cgroup, &count) in &cgroups {
+                let previous = self.last_cgroups.get(cgroup).copied().unwrap_or(0);
+                if count > previous {
+                    new_events.push(OomEvent {
+                        timestamp: now,
+                        cgroup: Some(cgroup.clone()),
+                        pid: None,
+                        process_name: None,
+                        process_memory_mb: None,
+                        kills: count - previous,
+                    });
+                }
+            }
+
+            // 全局计数的增量多于各 cgroup 增量之和：剩余部分记为全局 OOM
+            let global_delta = match (global, self.last_global) {
+                (Some(now), Some(before)) => now.saturating_sub(before),
+                _ => 0,
+            };
+            let cgroup_delta: u64 = new_events.iter().map(|e| e.kills).sum();
+            if global_delta > cgroup_delta {
+                new_events.push(OomEvent {
+                    timestamp: now,
+                    cgroup: None,
+                    pid: None,
+                    process_name: None,
+                    process_memory_mb: None,
+                    kills: global_delta - cgroup_delta,
+                });
+            }
+        }
+
+        if !new_events.is_empty() {
+            // 被杀进程只能在其所属 cgroup 的事件中确定：取该 cgroup 中消失的、内存最大的进程
+            // 全局 OOM 无法区分被杀和正常退出的进程，不做推测
+            for event in new_events.iter_mut() {
+                let Some(cgroup) = event.cgroup.as_deref() else {
+                    continue;
+                };
+                let victim = self
+                    .last_processes
+                    .iter()
+                    .filter(|(pid, process)| {
+                        !processes.contains_key(pid) && process.cgroup.as_deref() == Some(cgroup)
+                    })
+                    .max_by(|a, b| a.1.memory_mb.total_cmp(&b.1.memory_mb));
+                if let Some((pid, process)) = victim {
+                    event.pid = Some(*pid);
+                    event.process_name = Some(process.name.clone());
+                    event.process_memory_mb = Some(process.memory_mb);
+                }
+            }
+
+            for event in new_events {
+                self.events.push_front(event);
+            }
+            self.events.truncate(MAX_OOM_EVENTS);
+        }
+
+        self.initialized = true;
+        self.last_global = global;
+        self.last_cgroups = cgroups;
+        // 进程的 cgroup 很少变化，只为新出现的进程读取 /proc/<pid>/cgroup
+        let mut last_processes = std::mem::take(&mut self.last_processes);
+        self.last_processes = processes
+            .into_iter()
+            .map(|(pid, (name, memory_mb))| {
+                let cgroup = match last_processes.remove(&pid) {
+                    Some(previous) => previous.cgroup,
+                    None => read_process_cgroup(proc_root, pid),
+                };
+                (
+                    pid,
+                    TrackedProcess {
+                        name,
+                        memory_mb,
+                        cgroup,
+                    },
+                )
+            })
+            .collect();
+
+        OomStats {
+            total_kills: global,
+            recent_events: self.events.iter().cloned().collect(),
+        }
+    }
+}
+
+/// 递归扫描 cgroup 树，收集每个 cgroup 的 oom_kill 计数
+/// cgroup v2 读 memory.events.local（只含该 cgroup 自身的击杀），v1 读 memory.oom_control；
+/// v2 的 memory.events 会把子 cgroup 的击杀累加到所有祖先上，不能用来定位，
+/// 因此没有 .local 文件的 v2 cgroup（5.7 之前的内核）直接跳过
+fn scan_cgroups(root: &Path, dir: &Path, depth: usize, out: &mut HashMap<String, u64>) {
+    let file = if dir.join("memory.events").exists() {
+        "memory.events.local"
+    } else {
+        "memory.oom_control"
+    };
+    let count = fs::read_to_string(dir.join(file))
+        .ok()
+        .and_then(|content| parse_oom_kill(&content));
+
+    if let Some(count) = count {
+        let name = dir
+            .strip_prefix(root)
+            .map(|p| format!("/{}", p.to_string_lossy()))
+            .unwrap_or_default();
+        out.insert(name, count);
+    }
+
+    if depth >= CGROUP_SCAN_DEPTH {
+        return;
+    }
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                scan_cgroups(root, &entry.path(), depth + 1, out);
+            }
+        }
+    }
+}
+
+/// 读取进程所属的内存 cgroup，转换为 scan_cgroups 使用的名称（相对 cgroup 根目录）
+fn read_process_cgroup(proc_root: &Path, pid: u32) -> Option<String> {
+    let content = fs::read_to_string(proc_root.join(pid.to_string()).join("cgroup")).ok()?;
+    parse_process_cgroup(&content)
+}
+
+/// 解析 /proc/<pid>/cgroup（`层级 ID:控制器:路径`）：
+/// v1 / 混合模式取 memory 控制器的层级（挂载在 cgroup 根目录的 memory 子目录下），
+/// 纯 v2 取 `0::` 行
+pub fn parse_process_cgroup(content: &str) -> Option<String> {
+    let entries: Vec<(&str, &str)> = content
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(':')?;
+            rest.split_once(':')
+        })
+        .collect();
+    let path = match entries
+        .iter()
+        .find(|(controllers, _)| controllers.split(',').any(|c| c == "memory"))
+    {
+        Some((_, path)) => format!("/memory{}", path),
+        None => entries
+            .iter()
+            .find(|(controllers, _)| controllers.is_empty())?
+            .1
+            .to_string(),
+    };
+    let trimmed = path.trim_end_matches('/');
+    Some(if trimmed.is_empty() {
+        "/".to_string()
+    } else {
+        trimmed.to_string()
+    })
+}
+
+/// 从 `key value` 形式的文件中取出 oom_kill 计数
+pub fn parse_oom_kill(content: &str) -> Option<u64> {
+    content.lines().find_map(|line| {
+        let (key, value) = line.split_once(' ')?;
+        if key == "oom_kill" {
+            value.trim().parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::FixtureTree;
+
+    fn events(oom_kill: u64) -> String {
+        format!(
+            "low 0\nhigh 0\nmax 12\noom {}\noom_kill {}\n",
+            oom_kill, oom_kill
+        )
+    }
+
+    fn vmstat(oom_kill: u64) -> VmStat {
+        VmStat::from([("oom_kill".to_string(), oom_kill)])
+    }
+
+    #[test]
+    fn parses_oom_kill_line() {
+        assert_eq!(parse_oom_kill(&events(3)), Some(3));
+        assert_eq!(
+            parse_oom_kill("oom_kill_disable 0\nunder_oom 0\noom_kill 7\n"),
+            Some(7)
+        );
+        assert_eq!(parse_oom_kill("oom_kill_disable 0\nunder_oom 0\n"), None);
+    }
+
+    #[test]
+    fn scans_only_local_and_v1_counters() {
+        let tree = FixtureTree::new("oom-scan");
+        tree.write("memory.events", &events(9))
+            .write("memory.events.local", &events(0))
+            .write("system.slice/memory.events", &events(2))
+            .write("system.slice/memory.events.local", &events(2))
+            // 没有 .local 的 v2 cgroup，层级计数不可靠
+            .write("old.slice/memory.events", &events(5))
+            .write(
+                "memory/docker/memory.oom_control",
+                "oom_kill_disable 0\nunder_oom 0\noom_kill 4\n",
+            );
+
+        let mut out = HashMap::new();
+        scan_cgroups(&tree.root, &tree.root, 0, &mut out);
+        assert_eq!(out.get("/"), Some(&0));
+        assert_eq!(out.get("/system.slice"), Some(&2));
+        assert_eq!(out.get("/old.slice"), None);
+        assert_eq!(out.get("/memory/docker"), Some(&4));
+    }
+
+    #[test]
+    fn rescans_only_when_global_counter_grows() {
+        let tree = FixtureTree::new("oom-rescan");
+        tree.write("app.slice/memory.events", &events(1))
+            .write("app.slice/memory.events.local", &events(1));
+
+        let proc_root = tree.root.join("proc");
+        let mut tracker = OomTracker::new();
+        let stats = tracker.update(
+            Some(&vmstat(1)),
+            &tree.root,
+            &proc_root,
+            ProcessSnapshot::new(),
+        );
+        assert!(stats.recent_events.is_empty());
+
+        // 全局计数未变：不重新扫描，cgroup 文件的变化暂不可见
+        tree.write("app.slice/memory.events.local", &events(2));
+        let stats = tracker.update(
+            Some(&vmstat(1)),
+            &tree.root,
+            &proc_root,
+            ProcessSnapshot::new(),
+        );
+        assert!(stats.recent_events.is_empty());
+
+        tree.write("proc/42/cgroup", "0::/app.slice\n");
+        let processes = ProcessSnapshot::from([(42, ("java".to_string(), 2048.0))]);
+        tracker.update(Some(&vmstat(1)), &tree.root, &proc_root, processes);
+        let stats = tracker.update(
+            Some(&vmstat(2)),
+            &tree.root,
+            &proc_root,
+            ProcessSnapshot::new(),
+        );
+        assert_eq!(stats.recent_events.len(), 1);
+        let event = &stats.recent_events[0];
+        assert_eq!(event.cgroup.as_deref(), Some("/app.slice"));
+        assert_eq!(event.kills, 1);
+        assert_eq!(event.pid, Some(42));
+        assert_eq!(stats.total_kills, Some(2));
+    }
+
+    #[test]
+    fn global_kill_without_cgroup_increase() {
+        let tree = FixtureTree::new("oom-global");
+        tree.write("memory.events.local", &events(0));
+
+        tree.write("proc/7/cgroup", "0::/user.slice\n");
+        let proc_root = tree.root.join("proc");
+
+        let mut tracker = OomTracker::new();
+        let processes = ProcessSnapshot::from([(7, ("bash".to_string(), 4.0))]);
+        tracker.update(Some(&vmstat(5)), &tree.root, &proc_root, processes);
+        let stats = tracker.update(
+            Some(&vmstat(6)),
+            &tree.root,
+            &proc_root,
+            ProcessSnapshot::new(),
+        );
+        assert_eq!(stats.recent_events.len(), 1);
+        let event = &stats.recent_events[0];
+        assert!(event.cgroup.is_none());
+        // 全局 OOM 不把消失的进程当作被杀进程
+        assert_eq!(event.pid, None);
+    }
+
+    #[test]
+    fn attributes_only_processes_in_the_event_cgroup() {
+        let tree = FixtureTree::new("oom-attribute");
+        tree.write("app.slice/memory.events", &events(0))
+            .write("app.slice/memory.events.local", &events(0))
+            .write("proc/10/cgroup", "0::/app.slice\n")
+            .write("proc/11/cgroup", "0::/app.slice\n")
+            .write("proc/20/cgroup", "0::/user.slice/session-1.scope\n");
+        let proc_root = tree.root.join("proc");
+
+        let mut tracker = OomTracker::new();
+        let processes = ProcessSnapshot::from([
+            (10, ("worker".to_string(), 300.0)),
+            (11, ("cache".to_string(), 900.0)),
+            (20, ("firefox".to_string(), 4000.0)),
+        ]);
+        tracker.update(Some(&vmstat(0)), &tree.root, &proc_root, processes);
+
+        // 其他 cgroup 中更大的进程同时退出，不影响归属
+        tree.write("app.slice/memory.events.local", &events(1));
+        let processes = ProcessSnapshot::from([(10, ("worker".to_string(), 300.0))]);
+        let stats = tracker.update(Some(&vmstat(1)), &tree.root, &proc_root, processes);
+        let event = &stats.recent_events[0];
+        assert_eq!(event.pid, Some(11));
+        assert_eq!(event.process_name.as_deref(), Some("cache"));
+        assert_eq!(event.process_memory_mb, Some(900.0));
+
+        // 该 cgroup 中没有消失的进程时不做归属
+        tree.write("app.slice/memory.events.local", &events(2));
+        let processes = ProcessSnapshot::from([(10, ("worker".to_string(), 300.0))]);
+        let stats = tracker.update(Some(&vmstat(2)), &tree.root, &proc_root, processes);
+        assert_eq!(stats.recent_events[0].pid, None);
+    }
+
+    #[test]
+    fn parses_process_cgroup() {
+        assert_eq!(
+            parse_process_cgroup("0::/system.slice/docker-1f2e.scope\n").as_deref(),
+            Some("/system.slice/docker-1f2e.scope")
+        );
+        assert_eq!(parse_process_cgroup("0::/\n").as_deref(), Some("/"));
+        // v1 / 混合模式：memory 控制器的层级挂载在 memory 子目录下
+        let hybrid = "12:cpuset:/\n5:memory,hugetlb:/docker/abc\n1:name=systemd:/docker/abc\n0::/docker/abc\n";
+        assert_eq!(
+            parse_process_cgroup(hybrid).as_deref(),
+            Some("/memory/docker/abc")
+        );
+        assert_eq!(
+            parse_process_cgroup("4:memory:/\n").as_deref(),
+            Some("/memory")
+        );
+        assert_eq!(parse_process_cgroup("3:cpu,cpuacct:/\n"), None);
+    }
+}
@@ -0,0 +1,83 @@
+// 常见显卡的 PCI ID 子集（完整列表见 https://pci-ids.ucw.cz），
+// 用于没有驱动提供型号名称时显示友好的名称
+
+pub const VENDOR_NVIDIA: u16 = 0x10de;
+pub const VENDOR_AMD: u16 = 0x1002;
+pub const VENDOR_INTEL: u16 = 0x8086;
+
+const VENDORS: &[(u16, &str)] = &[
+    (VENDOR_NVIDIA, "NVIDIA"),
+    (VENDOR_AMD, "AMD"),
+    (VENDOR_INTEL, "Intel"),
+    (0x1a03, "ASPEED"),
+    (0x102b, "Matrox"),
+    (0x15ad, "VMware"),
+    (0x1af4, "Red Hat (virtio)"),
+    (0x1234, "QEMU"),
+];
+
+const DEVICES: &[(u16, u16, &str)] = &[
+    // NVIDIA
+    (VENDOR_NVIDIA, 0x1b06, "GeForce GTX 1080 Ti"),
+    (VENDOR_NVIDIA, 0x1b80, "GeForce GTX 1080"),
+    (VENDOR_NVIDIA, 0x1c82, "GeForce GTX 1050 Ti"),
+    (VENDOR_NVIDIA, 0x1eb8, "Tesla T4"),
+    (VENDOR_NVIDIA, 0x20b0, "A100 SXM4 40GB"),
+    (VENDOR_NVIDIA, 0x20b5, "A100 PCIe 80GB"),
+    (VENDOR_NVIDIA, 0x2204, "GeForce RTX 3090"),
+    (VENDOR_NVIDIA, 0x2206, "GeForce RTX 3080"),
+    (VENDOR_NVIDIA, 0x2330, "H100 SXM5 80GB"),
+    (VENDOR_NVIDIA, 0x2331, "H100 PCIe"),
+    (VENDOR_NVIDIA, 0x2484, "GeForce RTX 3070"),
+    (VENDOR_NVIDIA, 0x2503, "GeForce RTX 3060"),
+    (VENDOR_NVIDIA, 0x2684, "GeForce RTX 4090"),
+    (VENDOR_NVIDIA, 0x2704, "GeForce RTX 4080"),
+    (VENDOR_NVIDIA, 0x2782, "GeForce RTX 4070 Ti"),
+    (VENDOR_NVIDIA, 0x2786, "GeForce RTX 4070"),
+    // AMD
+    (VENDOR_AMD, 0x1636, "Radeon Graphics (Renoir)"),
+    (VENDOR_AMD, 0x1638, "Radeon Graphics (Cezanne)"),
+    (VENDOR_AMD, 0x15bf, "Radeon 780M (Phoenix)"),
+    (VENDOR_AMD, 0x164e, "Radeon Graphics (Raphael)"),
+    (VENDOR_AMD, 0x67df, "Radeon RX 470/480/570/580"),
+    (VENDOR_AMD, 0x731f, "Radeon RX 5600/5700 (Navi 10)"),
+    (VENDOR_AMD, 0x73bf, "Radeon RX 6800/6900 (Navi 21)"),
+    (VENDOR_AMD, 0x73df, "Radeon RX 6700 (Navi 22)"),
+    (VENDOR_AMD, 0x73ff, "Radeon RX 6600 (Navi 23)"),
+    (VENDOR_AMD, 0x740f, "Instinct MI210"),
+    (VENDOR_AMD, 0x744c, "Radeon RX 7900 (Navi 31)"),
+    (VENDOR_AMD, 0x7480, "Radeon RX 7600 (Navi 33)"),
+    (VENDOR_AMD, 0x74a1, "Instinct MI300X"),
+    // Intel
+    (VENDOR_INTEL, 0x1912, "HD Graphics 530"),
+    (VENDOR_INTEL, 0x3e92, "UHD Graphics 630"),
+    (VENDOR_INTEL, 0x3e9b, "UHD Graphics 630"),
+    (VENDOR_INTEL, 0x4680, "UHD Graphics 770"),
+    (VENDOR_INTEL, 0x4692, "UHD Graphics 730"),
+    (VENDOR_INTEL, 0x46a6, "Iris Xe Graphics (Alder Lake)"),
+    (VENDOR_INTEL, 0x56a0, "Arc A770"),
+    (VENDOR_INTEL, 0x56a1, "Arc A750"),
+    (VENDOR_INTEL, 0x56a5, "Arc A380"),
+    (VENDOR_INTEL, 0x5912, "HD Graphics 630"),
+    (VENDOR_INTEL, 0x5917, "UHD Graphics 620"),
+    (VENDOR_INTEL, 0x7d55, "Arc Graphics (Meteor Lake)"),
+    (VENDOR_INTEL, 0x9a49, "Iris Xe Graphics (Tiger Lake)"),
+    (VENDOR_INTEL, 0x9bc5, "UHD Graphics 630"),
+    (VENDOR_INTEL, 0xa780, "UHD Graphics 770"),
+    // 服务器 BMC 集成的显示控制器
+    (0x1a03, 0x2000, "ASPEED Graphics Family"),
+];
+
+pub fn vendor_name(vendor: u16) -> Option<&'static str> {
+    VENDORS
+        .iter()
+        .find(|(id, _)| *id == vendor)
+        .map(|(_, name)| *name)
+}
+
+pub fn device_name(vendor: u16, device: u16) -> Option<&'static str> {
+    DEVICES
+        .iter()
+        .find(|(v, d, _)| *v == vendor && *d == device)
+        .map(|(_, _, name)| *name)
+}
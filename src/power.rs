@@ -0,0 +1,294 @@
+use crate::sysfs::{read_string, read_u64};
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+
+// RAPL（Running Average Power Limit）能量计数器，Intel 和较新内核下的 AMD Zen 都在这里
+// 自 CVE-2020-8694 起 energy_uj 只有 root 可读
+pub const POWERCAP_ROOT: &str = "/sys/class/powercap";
+
+// 保留的每日用电量天数（含今天）
+const DAILY_HISTORY_DAYS: usize = 7;
+
+#[derive(Serialize, Clone, Default)]
+pub struct PowerStats {
+    // 找到可读的 RAPL 计数器时为 true
+    pub available: bool,
+    pub domains: Vec<PowerDomain>,
+    // 整机（可测部分）功耗：有 psys 时取 psys，否则为各 package 与 dram 之和
+    pub total_watts: Option<f64>,
+    // 最近几天的累计用电量，最后一项为今天（进程重启后从 0 开始）
+    pub daily_energy: Vec<DailyEnergy>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct PowerDomain {
+    // powercap 目录名，如 intel-rapl:0、intel-rapl:0:1
+    pub id: String,
+    // 域名称：package-0 / core / uncore / dram / psys
+    pub name: String,
+    // 所属 package（子域为父域名称，顶层域为空）
+    pub parent: Option<String>,
+    // 两次采样间的平均功耗，首次采样时为空
+    pub watts: Option<f64>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DailyEnergy {
+    pub date: String,
+    pub kwh: f64,
+}
+
+/// 一个 RAPL 域的能量计数器读数
+#[derive(Clone)]
+pub struct RaplCounter {
+    pub id: String,
+    pub name: String,
+    pub parent: Option<String>,
+    pub energy_uj: u64,
+    // 计数器上限，超过后从 0 重新计数
+    pub max_energy_range_uj: u64,
+}
+
+/// 读取所有 intel-rapl:* 域
+/// intel-rapl-mmio:* 与 MSR 接口的 package 域重复，跳过以免重复计算
+pub fn read_rapl(powercap_root: &Path) -> Vec<RaplCounter> {
+    let Ok(entries) = fs::read_dir(powercap_root) else {
+        return Vec::new();
+    };
+    let mut ids: Vec<String> = entries
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|id| id.starts_with("intel-rapl:"))
+        .collect();
+    ids.sort();
+
+    ids.iter()
+        .filter_map(|id| {
+            let dir = powercap_root.join(id);
+            // 子域 intel-rapl:0:1 的父域为 intel-rapl:0
+            let parent = id
+                .rsplit_once(':')
+                .filter(|(parent, _)| parent.contains(':'))
+                .and_then(|(parent, _)| read_string(powercap_root.join(parent).join("name")));
+            Some(RaplCounter {
+                id: id.clone(),
+                name: read_string(dir.join("name"))?,
+                parent,
+                energy_uj: read_u64(dir.join("energy_uj"))?,
+                max_energy_range_uj: read_u64(dir.join("max_energy_range_uj")).unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+/// 两次读数之间消耗的能量（微焦），处理计数器回绕
+/// 读数变小但不知道计数器上限（max_energy_range_uj 读取失败或为 0）时无法计算，返回 None
+fn energy_delta(previous: &RaplCounter, current: &RaplCounter) -> Option<u64> {
+    if current.energy_uj >= previous.energy_uj {
+        Some(current.energy_uj - previous.energy_uj)
+    } else if current.max_energy_range_uj > 0 {
+        Some(
+            current
+                .max_energy_range_uj
+                .saturating_sub(previous.energy_uj)
+                + current.energy_uj,
+        )
+    } else {
+        None
+    }
+}
+
+/// 每日用电量累计
+pub struct EnergyTracker {
+    days: VecDeque<(NaiveDate, f64)>,
+}
+
+impl EnergyTracker {
+    pub fn new() -> Self {
+        Self {
+            days: VecDeque::new(),
+        }
+    }
+
+    /// 把本次采样间隔消耗的能量（焦耳）计入今天
+    fn add(&mut self, today: NaiveDate, joules: f64) {
+        if self.days.back().is_none_or(|(date, _)| *date != today) {
+            self.days.push_back((today, 0.0));
+            while self.days.len() > DAILY_HISTORY_DAYS {
+                self.days.pop_front();
+            }
+        }
+        if let Some((_, total)) = self.days.back_mut() {
+            *total += joules;
+        }
+    }
+
+    fn daily(&self) -> Vec<DailyEnergy> {
+        self.days
+            .iter()
+            .map(|(date, joules)| DailyEnergy {
+                date: date.to_string(),
+                kwh: joules / 3_600_000.0,
+            })
+            .collect()
+    }
+}
+
+/// 由两次 RAPL 读数计算各域功耗，并累计整机用电量
+pub fn power_stats(
+    previous: Option<(&[RaplCounter], f64)>,
+    current: &[RaplCounter],
+    energy: &mut EnergyTracker,
+) -> PowerStats {
+    if current.is_empty() {
+        return PowerStats::default();
+    }
+
+    let domains: Vec<PowerDomain> = current
+        .iter()
+        .map(|counter| {
+            let watts =
+                previous
+                    .filter(|(_, elapsed)| *elapsed > 0.0)
+                    .and_then(|(counters, elapsed)| {
+                        let before = counters.iter().find(|c| c.id == counter.id)?;
+                        Some(energy_delta(before, counter)? as f64 / 1_000_000.0 / elapsed)
+                    });
+            PowerDomain {
+                id: counter.id.clone(),
+                name: counter.name.clone(),
+                parent: counter.parent.clone(),
+                watts,
+            }
+        })
+        .collect();
+
+    // psys 覆盖整个 SoC；否则 package 不含内存，需要加上 dram 域
+    let psys: Vec<&PowerDomain> = domains.iter().filter(|d| d.name == "psys").collect();
+    let total_domains: Vec<&PowerDomain> = if psys.is_empty() {
+        domains
+            .iter()
+            .filter(|d| d.name.starts_with("package") || d.name == "dram")
+            .collect()
+    } else {
+        psys
+    };
+    let total_watts = total_domains
+        .iter()
+        .map(|d| d.watts)
+        .sum::<Option<f64>>()
+        .filter(|_| !total_domains.is_empty());
+
+    if let (Some(watts), Some((_, elapsed))) = (total_watts, previous) {
+        energy.add(Local::now().date_naive(), watts * elapsed);
+    }
+
+    PowerStats {
+        available: true,
+        domains,
+        total_watts,
+        daily_energy: energy.daily(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::FixtureTree;
+
+    fn counter(id: &str, name: &str, energy_uj: u64, max_energy_range_uj: u64) -> RaplCounter {
+        RaplCounter {
+            id: id.to_string(),
+            name: name.to_string(),
+            parent: None,
+            energy_uj,
+            max_energy_range_uj,
+        }
+    }
+
+    #[test]
+    fn reads_rapl_domains() {
+        let tree = FixtureTree::new("rapl");
+        tree.write("intel-rapl:0/name", "package-0\n")
+            .write("intel-rapl:0/energy_uj", "123456789\n")
+            .write("intel-rapl:0/max_energy_range_uj", "262143328850\n")
+            .write("intel-rapl:0:0/name", "core\n")
+            .write("intel-rapl:0:0/energy_uj", "5000\n")
+            // 非 root 时 energy_uj 不可读，该域跳过
+            .write("intel-rapl:1/name", "psys\n")
+            // MMIO 接口与 package 域重复
+            .write("intel-rapl-mmio:0/name", "package-0\n")
+            .write("intel-rapl-mmio:0/energy_uj", "1\n");
+
+        let counters = read_rapl(&tree.root);
+        let summary: Vec<_> = counters
+            .iter()
+            .map(|c| (&*c.id, &*c.name, c.parent.as_deref(), c.energy_uj))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("intel-rapl:0", "package-0", None, 123456789),
+                ("intel-rapl:0:0", "core", Some("package-0"), 5000),
+            ]
+        );
+        assert_eq!(counters[0].max_energy_range_uj, 262143328850);
+        assert_eq!(counters[1].max_energy_range_uj, 0);
+    }
+
+    #[test]
+    fn energy_delta_handles_wraparound() {
+        let max = 262_143_328_850;
+        let before = counter("intel-rapl:0", "package-0", max - 1_000_000, max);
+        let after = counter("intel-rapl:0", "package-0", 2_000_000, max);
+        assert_eq!(energy_delta(&before, &after), Some(3_000_000));
+        assert_eq!(energy_delta(&after, &after), Some(0));
+
+        // 不知道上限时读数变小无法计算
+        let before = counter("intel-rapl:0", "package-0", 5_000_000, 0);
+        let after = counter("intel-rapl:0", "package-0", 1_000_000, 0);
+        assert_eq!(energy_delta(&before, &after), None);
+    }
+
+    #[test]
+    fn computes_domain_and_total_power() {
+        let previous = vec![
+            counter("intel-rapl:0", "package-0", 1_000_000, 0),
+            counter("intel-rapl:1", "dram", 0, 0),
+        ];
+        let current = vec![
+            counter("intel-rapl:0", "package-0", 41_000_000, 0),
+            counter("intel-rapl:1", "dram", 4_000_000, 0),
+            counter("intel-rapl:0:0", "core", 10, 0),
+        ];
+        let mut energy = EnergyTracker::new();
+
+        let first = power_stats(None, &current, &mut energy);
+        assert!(first.available);
+        assert_eq!(first.total_watts, None);
+        assert!(first.daily_energy.is_empty());
+
+        let stats = power_stats(Some((&previous, 2.0)), &current, &mut energy);
+        let watts: Vec<_> = stats.domains.iter().map(|d| d.watts).collect();
+        assert_eq!(watts, vec![Some(20.0), Some(2.0), None]);
+        assert_eq!(stats.total_watts, Some(22.0));
+        assert_eq!(stats.daily_energy.len(), 1);
+        assert!((stats.daily_energy[0].kwh - 44.0 / 3_600_000.0).abs() < 1e-12);
+
+        // 计数器变小且没有上限时不计入功耗和用电量
+        let stats = power_stats(Some((&current, 2.0)), &previous, &mut energy);
+        assert_eq!(stats.domains[0].watts, None);
+        assert_eq!(stats.total_watts, None);
+        assert!((stats.daily_energy[0].kwh - 44.0 / 3_600_000.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn no_rapl_is_unavailable() {
+        let stats = power_stats(None, &[], &mut EnergyTracker::new());
+        assert!(!stats.available);
+        assert!(stats.domains.is_empty());
+    }
+}
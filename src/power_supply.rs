@@ -0,0 +1,248 @@
+use crate::sysfs::{read_string, read_u64};
+use battery::{Manager as BatteryManager, State};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+
+pub const POWER_SUPPLY_ROOT: &str = "/sys/class/power_supply";
+
+// 电量历史：每 30 秒记录一个点，用最近 20 分钟的变化估算续航
+const HISTORY_INTERVAL_SECS: i64 = 30;
+const HISTORY_WINDOW_SECS: i64 = 20 * 60;
+// 历史跨度不足该值时不估算（刚拔电源时波动大）
+const MIN_ESTIMATE_SPAN_SECS: i64 = 120;
+
+/// 所有电池的汇总（前几项字段与旧版保持一致）
+#[derive(Serialize, Clone)]
+pub struct BatteryInfo {
+    // 按能量加权的总电量
+    pub percentage: f32,
+    // 任一电池在充电；充满（Full）不算充电
+    pub is_charging: bool,
+    // 按驱动报告的瞬时放电功率计算的剩余时间，只在放电时提供
+    pub time_remaining_minutes: Option<i64>,
+    pub health_percent: f32,
+    // 交流电源是否接通，没有 Mains 类型电源时为空
+    pub ac_online: Option<bool>,
+    // 按最近的实际耗电速度估算的剩余续航 / 充满时间
+    pub estimated_runtime_minutes: Option<i64>,
+    pub estimated_full_minutes: Option<i64>,
+    pub batteries: Vec<BatteryDetail>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct BatteryDetail {
+    pub vendor: Option<String>,
+    pub model: Option<String>,
+    // charging / discharging / full / empty / unknown
+    pub state: String,
+    pub percentage: f32,
+    pub health_percent: f32,
+    pub energy_wh: f64,
+    pub energy_full_wh: f64,
+    // 当前充放电功率
+    pub energy_rate_watts: f64,
+    pub voltage: f64,
+    pub cycle_count: Option<u32>,
+}
+
+/// 电量历史（时间戳, 所有电池的剩余能量 Wh），充放电状态改变时清空
+pub struct ChargeHistory {
+    // Some(true) 充电，Some(false) 放电，None 既不充也不放（充满或接通电源待机）
+    charging: Option<bool>,
+    points: VecDeque<(i64, f64)>,
+}
+
+impl ChargeHistory {
+    pub fn new() -> Self {
+        Self {
+            charging: None,
+            points: VecDeque::new(),
+        }
+    }
+
+    fn record(&mut self, now: i64, charging: Option<bool>, energy_wh: f64) {
+        if charging != self.charging {
+            self.charging = charging;
+            self.points.clear();
+        }
+        if self
+            .points
+            .back()
+            .is_some_and(|(at, _)| now - at < HISTORY_INTERVAL_SECS)
+        {
+            return;
+        }
+        self.points.push_back((now, energy_wh));
+        while self
+            .points
+            .front()
+            .is_some_and(|(at, _)| now - at > HISTORY_WINDOW_SECS)
+        {
+            self.points.pop_front();
+        }
+    }
+
+    /// 历史窗口内的平均功率（W），放电为正
+    fn drain_watts(&self) -> Option<f64> {
+        let (first_at, first) = *self.points.front()?;
+        let (last_at, last) = *self.points.back()?;
+        let span = last_at - first_at;
+        if span < MIN_ESTIMATE_SPAN_SECS {
+            return None;
+        }
+        Some((first - last) / (span as f64 / 3600.0))
+    }
+}
+
+/// 采集所有电池，没有电池时返回 None
+/// 电池读数来自 battery crate（它处理了 energy_* / charge_* 两种接口的换算和各平台差异），
+/// 只有交流电源状态直接读取 power_supply_root（battery crate 不提供 Mains 类型电源）
+pub fn collect_battery_info(
+    power_supply_root: &Path,
+    history: &mut ChargeHistory,
+    now: i64,
+) -> Option<BatteryInfo> {
+    let manager = BatteryManager::new().ok()?;
+    let batteries: Vec<battery::Battery> = manager.batteries().ok()?.flatten().collect();
+    if batteries.is_empty() {
+        return None;
+    }
+
+    // 能量单位为焦耳，换算为 Wh
+    let wh = |joules: f32| joules as f64 / 3600.0;
+    let details: Vec<BatteryDetail> = batteries
+        .iter()
+        .map(|b| BatteryDetail {
+            vendor: b.vendor().map(|s| s.trim().to_string()),
+            model: b.model().map(|s| s.trim().to_string()),
+            state: b.state().to_string(),
+            percentage: b.state_of_charge().value * 100.0,
+            health_percent: b.state_of_health().value * 100.0,
+            energy_wh: wh(b.energy().value),
+            energy_full_wh: wh(b.energy_full().value),
+            energy_rate_watts: b.energy_rate().value as f64,
+            voltage: b.voltage().value as f64,
+            cycle_count: b.cycle_count(),
+        })
+        .collect();
+
+    let energy: f64 = details.iter().map(|b| b.energy_wh).sum();
+    let energy_full: f64 = details.iter().map(|b| b.energy_full_wh).sum();
+    let energy_design: f64 = batteries
+        .iter()
+        .map(|b| wh(b.energy_full_design().value))
+        .sum();
+    let is_charging = batteries.iter().any(|b| b.state() == State::Charging);
+    let discharging = batteries.iter().any(|b| b.state() == State::Discharging);
+
+    let direction = if is_charging {
+        Some(true)
+    } else if discharging {
+        Some(false)
+    } else {
+        None
+    };
+    history.record(now, direction, energy);
+    let drain = history.drain_watts();
+    let minutes = |wh: f64, watts: f64| (wh / watts * 60.0).round() as i64;
+
+    Some(BatteryInfo {
+        percentage: if energy_full > 0.0 {
+            (energy / energy_full * 100.0) as f32
+        } else {
+            details[0].percentage
+        },
+        is_charging,
+        // 多块电池依次放电，按总能量和当前总放电功率计算
+        time_remaining_minutes: Some(
+            batteries
+                .iter()
+                .zip(&details)
+                .filter(|(b, _)| b.state() == State::Discharging)
+                .map(|(_, detail)| detail.energy_rate_watts)
+                .sum::<f64>(),
+        )
+        .filter(|watts| discharging && *watts > 0.0)
+        .map(|watts| minutes(energy, watts)),
+        health_percent: if energy_design > 0.0 {
+            (energy_full / energy_design * 100.0) as f32
+        } else {
+            details[0].health_percent
+        },
+        ac_online: read_ac_online(power_supply_root),
+        estimated_runtime_minutes: drain
+            .filter(|w| discharging && *w > 0.0)
+            .map(|w| minutes(energy, w)),
+        estimated_full_minutes: drain
+            .filter(|w| is_charging && *w < 0.0)
+            .map(|w| minutes(energy_full - energy, -w)),
+        batteries: details,
+    })
+}
+
+/// 任一 Mains 类型电源 online 即视为接通交流电
+fn read_ac_online(power_supply_root: &Path) -> Option<bool> {
+    let entries = fs::read_dir(power_supply_root).ok()?;
+    let online: Vec<bool> = entries
+        .flatten()
+        .filter(|e| read_string(e.path().join("type")).as_deref() == Some("Mains"))
+        .filter_map(|e| read_u64(e.path().join("online")))
+        .map(|online| online == 1)
+        .collect();
+    (!online.is_empty()).then(|| online.contains(&true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::FixtureTree;
+
+    #[test]
+    fn reads_ac_online_from_mains_supplies() {
+        let tree = FixtureTree::new("power-supply");
+        assert_eq!(read_ac_online(&tree.root), None);
+
+        tree.write("BAT0/type", "Battery\n")
+            .write("BAT0/online", "1\n")
+            .write("AC/type", "Mains\n")
+            .write("AC/online", "0\n");
+        assert_eq!(read_ac_online(&tree.root), Some(false));
+
+        // USB-C 充电器等多个 Mains 电源，任一接通即可
+        tree.write("ucsi-source-psy-USBC000:001/type", "Mains\n")
+            .write("ucsi-source-psy-USBC000:001/online", "1\n");
+        assert_eq!(read_ac_online(&tree.root), Some(true));
+    }
+
+    #[test]
+    fn charge_history_estimates_drain() {
+        let mut history = ChargeHistory::new();
+        history.record(0, Some(false), 50.0);
+        // 记录间隔内的采样忽略
+        history.record(10, Some(false), 49.0);
+        assert_eq!(history.drain_watts(), None);
+
+        history.record(60, Some(false), 49.5);
+        // 跨度不足时不估算
+        assert_eq!(history.drain_watts(), None);
+        history.record(360, Some(false), 48.0);
+        assert_eq!(history.drain_watts(), Some(20.0));
+
+        // 开始充电时清空历史
+        history.record(400, Some(true), 48.0);
+        history.record(580, Some(true), 49.0);
+        assert_eq!(history.drain_watts(), Some(-20.0));
+    }
+
+    #[test]
+    fn charge_history_keeps_window() {
+        let mut history = ChargeHistory::new();
+        for minute in 0..=30 {
+            history.record(minute * 60, Some(false), 60.0 - minute as f64);
+        }
+        assert_eq!(history.points.front().map(|(at, _)| *at), Some(10 * 60));
+        assert_eq!(history.drain_watts(), Some(60.0));
+    }
+}
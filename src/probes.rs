@@ -0,0 +1,520 @@
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, RwLock, mpsc};
+use std::time::{Duration, Instant};
+
+const DEFAULT_PROBE_INTERVAL_SECS: u64 = 30;
+const DEFAULT_PROBE_TIMEOUT_SECS: u64 = 5;
+// 每个探测保留的历史结果数（丢包率、平均延迟按此窗口计算）
+const PROBE_HISTORY_LEN: usize = 30;
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProbeKind {
+    Tcp,
+    Http,
+    Dns,
+    Icmp,
+}
+
+/// 一个探测目标，配置格式 `名称=类型://目标`
+#[derive(Clone)]
+pub struct ProbeTarget {
+    pub name: String,
+    pub kind: ProbeKind,
+    // tcp: host:port；http: 完整 URL；dns: 域名；icmp: 主机
+    pub target: String,
+}
+
+/// 探测配置
+/// - MONITOR_PROBES：逗号分隔的探测目标，如
+///   `gw=icmp://192.168.1.1,web=http://example.com/health,db=tcp://10.0.0.5:5432,dns=dns://example.com`
+/// - MONITOR_PROBE_INTERVAL_SECS：探测间隔，默认 30 秒
+/// - MONITOR_PROBE_TIMEOUT_SECS：单次探测超时，默认 5 秒
+#[derive(Clone)]
+pub struct ProbeConfig {
+    pub targets: Vec<ProbeTarget>,
+    pub interval: Duration,
+    pub timeout: Duration,
+}
+
+impl ProbeConfig {
+    pub fn from_env() -> Self {
+        let secs = |name: &str, default: u64| {
+            Duration::from_secs(
+                std::env::var(name)
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .filter(|v| *v > 0)
+                    .unwrap_or(default),
+            )
+        };
+        Self {
+            targets: std::env::var("MONITOR_PROBES")
+                .map(|v| parse_probe_targets(&v))
+                .unwrap_or_default(),
+            interval: secs("MONITOR_PROBE_INTERVAL_SECS", DEFAULT_PROBE_INTERVAL_SECS),
+            timeout: secs("MONITOR_PROBE_TIMEOUT_SECS", DEFAULT_PROBE_TIMEOUT_SECS),
+        }
+    }
+}
+
+/// 解析探测目标列表，无效的项记录警告后忽略
+pub fn parse_probe_targets(value: &str) -> Vec<ProbeTarget> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .filter_map(|item| match parse_probe_target(item) {
+            Ok(target) => Some(target),
+            Err(reason) => {
+                tracing::warn!("忽略探测目标 `{}`：{}", item, reason);
+                None
+            }
+        })
+        .collect()
+}
+
+fn parse_probe_target(item: &str) -> Result<ProbeTarget, String> {
+    let (name, target) = item.split_once('=').ok_or("格式应为 名称=类型://目标")?;
+    let (scheme, rest) = target.split_once("://").ok_or("缺少类型前缀，如 tcp://")?;
+    if name.trim().is_empty() || rest.is_empty() {
+        return Err("名称或目标为空".to_string());
+    }
+    let (kind, target) = match scheme {
+        "tcp" => (ProbeKind::Tcp, rest.to_string()),
+        "http" => (ProbeKind::Http, target.to_string()),
+        "dns" => (ProbeKind::Dns, rest.to_string()),
+        "icmp" => (ProbeKind::Icmp, rest.to_string()),
+        "https" => return Err("不支持 https，可改用 tcp://主机:443 检查端口".to_string()),
+        _ => return Err(format!("未知的探测类型 {}", scheme)),
+    };
+    Ok(ProbeTarget {
+        name: name.trim().to_string(),
+        kind,
+        target,
+    })
+}
+
+#[derive(Serialize, Clone)]
+pub struct ProbeSample {
+    // 探测时间（Unix 秒）
+    pub timestamp: i64,
+    pub success: bool,
+    pub latency_ms: Option<f64>,
+    // HTTP 状态码
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ProbeStatus {
+    pub name: String,
+    pub kind: ProbeKind,
+    pub target: String,
+    pub last: Option<ProbeSample>,
+    // 最近的探测结果（按时间顺序）
+    pub history: VecDeque<ProbeSample>,
+    // 历史窗口内的失败比例和成功探测的平均延迟
+    pub loss_percent: f64,
+    pub avg_latency_ms: Option<f64>,
+    pub consecutive_failures: u32,
+}
+
+impl ProbeStatus {
+    fn new(target: &ProbeTarget) -> Self {
+        Self {
+            name: target.name.clone(),
+            kind: target.kind,
+            target: target.target.clone(),
+            last: None,
+            history: VecDeque::new(),
+            loss_percent: 0.0,
+            avg_latency_ms: None,
+            consecutive_failures: 0,
+        }
+    }
+
+    fn record(&mut self, sample: ProbeSample) {
+        if sample.success {
+            self.consecutive_failures = 0;
+        } else {
+            self.consecutive_failures += 1;
+        }
+        self.history.push_back(sample.clone());
+        while self.history.len() > PROBE_HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.last = Some(sample);
+
+        let failures = self.history.iter().filter(|s| !s.success).count();
+        self.loss_percent = failures as f64 / self.history.len() as f64 * 100.0;
+        let latencies: Vec<f64> = self
+            .history
+            .iter()
+            .filter(|s| s.success)
+            .filter_map(|s| s.latency_ms)
+            .collect();
+        self.avg_latency_ms = if latencies.is_empty() {
+            None
+        } else {
+            Some(latencies.iter().sum::<f64>() / latencies.len() as f64)
+        };
+    }
+}
+
+/// 所有探测的最新状态（由探测线程更新）
+pub type ProbeResults = Arc<RwLock<Vec<ProbeStatus>>>;
+
+/// 启动探测线程；没有配置探测目标时返回空列表
+pub fn spawn(config: &ProbeConfig) -> ProbeResults {
+    let results: ProbeResults = Arc::new(RwLock::new(
+        config.targets.iter().map(ProbeStatus::new).collect(),
+    ));
+    if config.targets.is_empty() {
+        return results;
+    }
+
+    let writer = results.clone();
+    let config = config.clone();
+    std::thread::spawn(move || {
+        let mut sequence: u16 = 0;
+        loop {
+            for (index, target) in config.targets.iter().enumerate() {
+                sequence = sequence.wrapping_add(1);
+                let sample = run_probe(target, config.timeout, sequence);
+                if let Ok(mut results) = writer.write() {
+                    results[index].record(sample);
+                }
+            }
+            std::thread::sleep(config.interval);
+        }
+    });
+
+    results
+}
+
+/// 执行一次探测
+/// 延迟从域名解析完成后开始计时（DNS 探测除外），解析慢不会算到连接延迟里
+pub fn run_probe(target: &ProbeTarget, timeout: Duration, sequence: u16) -> ProbeSample {
+    let result = match target.kind {
+        ProbeKind::Tcp => probe_tcp(&target.target, timeout).map(|(_, latency)| (None, latency)),
+        ProbeKind::Http => {
+            probe_http(&target.target, timeout).map(|(code, latency)| (Some(code), latency))
+        }
+        ProbeKind::Dns => {
+            let started = Instant::now();
+            resolve(&target.target, timeout).map(|_| (None, started.elapsed()))
+        }
+        ProbeKind::Icmp => {
+            probe_icmp(&target.target, timeout, sequence).map(|latency| (None, latency))
+        }
+    };
+
+    match result {
+        Ok((status_code, latency)) => {
+            let latency_ms = latency.as_secs_f64() * 1000.0;
+            // HTTP 4xx / 5xx 视为失败，但仍记录延迟和状态码
+            let success = status_code.is_none_or(|code| code < 400);
+            ProbeSample {
+                timestamp: Utc::now().timestamp(),
+                success,
+                latency_ms: Some(latency_ms),
+                status_code,
+                error: (!success).then(|| format!("HTTP {}", status_code.unwrap_or(0))),
+            }
+        }
+        Err(error) => ProbeSample {
+            timestamp: Utc::now().timestamp(),
+            success: false,
+            latency_ms: None,
+            status_code: None,
+            error: Some(error),
+        },
+    }
+}
+
+/// 域名解析（系统解析器，getaddrinfo 没有超时参数，放到单独线程等待）
+fn resolve(host: &str, timeout: Duration) -> Result<Vec<IpAddr>, String> {
+    let (tx, rx) = mpsc::channel();
+    let host = host.to_string();
+    std::thread::spawn(move || {
+        let result = (host.as_str(), 0)
+            .to_socket_addrs()
+            .map(|addrs| addrs.map(|a| a.ip()).collect::<Vec<_>>());
+        let _ = tx.send(result);
+    });
+    match rx.recv_timeout(timeout) {
+        Ok(Ok(addrs)) if !addrs.is_empty() => Ok(addrs),
+        Ok(Ok(_)) => Err("域名没有地址".to_string()),
+        Ok(Err(e)) => Err(format!("解析失败: {}", e)),
+        Err(_) => Err("解析超时".to_string()),
+    }
+}
+
+/// 解析 `host:port`（支持 `[::1]:80`）
+fn resolve_socket_addr(target: &str, timeout: Duration) -> Result<SocketAddr, String> {
+    if let Ok(addr) = target.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    let (host, port) = target
+        .rsplit_once(':')
+        .ok_or_else(|| "缺少端口".to_string())?;
+    let port: u16 = port.parse().map_err(|_| "端口无效".to_string())?;
+    let ip = resolve(host.trim_matches(['[', ']']), timeout)?[0];
+    Ok(SocketAddr::new(ip, port))
+}
+
+/// 建立 TCP 连接，返回连接和握手耗时（不含域名解析）
+fn probe_tcp(target: &str, timeout: Duration) -> Result<(TcpStream, Duration), String> {
+    let addr = resolve_socket_addr(target, timeout)?;
+    let started = Instant::now();
+    let stream =
+        TcpStream::connect_timeout(&addr, timeout).map_err(|e| format!("连接失败: {}", e))?;
+    Ok((stream, started.elapsed()))
+}
+
+/// 最小的 HTTP/1.1 GET，只读取状态行；不支持 https
+/// 返回状态码和从开始连接到收到状态行的耗时
+fn probe_http(url: &str, timeout: Duration) -> Result<(u16, Duration), String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| "仅支持 http:// 地址".to_string())?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let has_port = authority
+        .rsplit_once(':')
+        .is_some_and(|(_, p)| !p.contains(']'));
+    let target = if has_port {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+
+    let addr = resolve_socket_addr(&target, timeout)?;
+    let started = Instant::now();
+    let mut stream =
+        TcpStream::connect_timeout(&addr, timeout).map_err(|e| format!("连接失败: {}", e))?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        .map_err(|e| e.to_string())?;
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: system-monitor\r\nConnection: close\r\n\r\n",
+        path, authority
+    )
+    .map_err(|e| format!("发送失败: {}", e))?;
+
+    // 状态行：`HTTP/1.1 200 OK`
+    let mut status_line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut status_line)
+        .map_err(|e| format!("读取响应失败: {}", e))?;
+    let code = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| "响应格式无效".to_string())?;
+    Ok((code, started.elapsed()))
+}
+
+/// ICMP echo，使用无需 root 的 ping socket（SOCK_DGRAM + IPPROTO_ICMP），
+/// 需要当前用户组在 net.ipv4.ping_group_range 范围内；仅支持 IPv4
+#[cfg(target_os = "linux")]
+fn probe_icmp(host: &str, timeout: Duration, sequence: u16) -> Result<Duration, String> {
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    let ip = resolve(host, timeout)?
+        .into_iter()
+        .find_map(|ip| match ip {
+            IpAddr::V4(v4) => Some(v4),
+            IpAddr::V6(_) => None,
+        })
+        .ok_or_else(|| "没有 IPv4 地址".to_string())?;
+
+    // SAFETY: 只传入合法参数，返回的 fd 立即交给 OwnedFd 负责关闭
+    let raw = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM, libc::IPPROTO_ICMP) };
+    if raw < 0 {
+        return Err("无权限发送 ICMP（检查 net.ipv4.ping_group_range）".to_string());
+    }
+    let socket = unsafe { OwnedFd::from_raw_fd(raw) };
+    let fd = socket.as_raw_fd();
+
+    let tv = libc::timeval {
+        tv_sec: timeout.as_secs() as libc::time_t,
+        tv_usec: timeout.subsec_micros() as libc::suseconds_t,
+    };
+    // SAFETY: tv 在调用期间有效，长度与类型一致
+    unsafe {
+        libc::setsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_RCVTIMEO,
+            &tv as *const libc::timeval as *const libc::c_void,
+            std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+        );
+    }
+
+    // echo request：type 8，code 0；ping socket 由内核填写标识符和校验和
+    let seq = sequence.to_be_bytes();
+    let packet = [8u8, 0, 0, 0, 0, 0, seq[0], seq[1]];
+    let dest = libc::sockaddr_in {
+        sin_family: libc::AF_INET as libc::sa_family_t,
+        sin_port: 0,
+        sin_addr: libc::in_addr {
+            s_addr: u32::from_ne_bytes(ip.octets()),
+        },
+        sin_zero: [0; 8],
+    };
+    let started = Instant::now();
+    // SAFETY: packet 和 dest 在调用期间有效
+    let sent = unsafe {
+        libc::sendto(
+            fd,
+            packet.as_ptr() as *const libc::c_void,
+            packet.len(),
+            0,
+            &dest as *const libc::sockaddr_in as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        )
+    };
+    if sent < 0 {
+        return Err(format!("发送失败: {}", std::io::Error::last_os_error()));
+    }
+
+    let deadline = started + timeout;
+    let mut buf = [0u8; 128];
+    while Instant::now() < deadline {
+        // SAFETY: buf 可写且长度正确
+        let n = unsafe { libc::recv(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+        if n < 0 {
+            break;
+        }
+        // echo reply：type 0，序号一致
+        if n >= 8 && buf[0] == 0 && buf[6..8] == seq {
+            return Ok(started.elapsed());
+        }
+    }
+    Err("请求超时".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn probe_icmp(_host: &str, _timeout: Duration, _sequence: u16) -> Result<Duration, String> {
+    Err("当前平台不支持 ICMP 探测".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    fn target(kind: ProbeKind, target: String) -> ProbeTarget {
+        ProbeTarget {
+            name: "test".to_string(),
+            kind,
+            target,
+        }
+    }
+
+    /// 在 127.0.0.1 的随机端口上返回固定的 HTTP 响应（只处理一个连接）
+    fn http_server(status_line: &'static str) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request);
+            let _ = write!(stream, "{}\r\nContent-Length: 0\r\n\r\n", status_line);
+        });
+        addr
+    }
+
+    #[test]
+    fn parses_targets_and_rejects_invalid() {
+        let targets = parse_probe_targets(
+            " gw=icmp://192.168.1.1, web=http://example.com/health,db=tcp://10.0.0.5:5432,\
+             secure=https://example.com,bad,x=ftp://host,dns=dns://example.com,",
+        );
+        let names: Vec<&str> = targets.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["gw", "web", "db", "dns"]);
+        assert_eq!(targets[1].target, "http://example.com/health");
+        assert_eq!(targets[2].target, "10.0.0.5:5432");
+
+        assert!(parse_probe_target("secure=https://example.com").is_err());
+        assert!(parse_probe_target("x=ftp://host").is_err());
+        assert!(parse_probe_target("no-scheme=example.com").is_err());
+        assert!(parse_probe_target("=tcp://host:1").is_err());
+        assert!(parse_probe_target("empty=tcp://").is_err());
+    }
+
+    #[test]
+    fn tcp_success() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let sample = run_probe(&target(ProbeKind::Tcp, addr.to_string()), TIMEOUT, 1);
+        assert!(sample.success);
+        assert!(sample.latency_ms.is_some());
+        assert!(sample.error.is_none());
+    }
+
+    #[test]
+    fn tcp_refused() {
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        // 监听已关闭，端口拒绝连接
+        let sample = run_probe(&target(ProbeKind::Tcp, addr.to_string()), TIMEOUT, 1);
+        assert!(!sample.success);
+        assert!(sample.latency_ms.is_none());
+        assert!(sample.error.unwrap().starts_with("连接失败"));
+    }
+
+    #[test]
+    fn http_success() {
+        let addr = http_server("HTTP/1.1 204 No Content");
+        let url = format!("http://{}/health", addr);
+        let sample = run_probe(&target(ProbeKind::Http, url), TIMEOUT, 1);
+        assert!(sample.success);
+        assert_eq!(sample.status_code, Some(204));
+    }
+
+    #[test]
+    fn http_error_status() {
+        let addr = http_server("HTTP/1.1 503 Service Unavailable");
+        let url = format!("http://{}/", addr);
+        let sample = run_probe(&target(ProbeKind::Http, url), TIMEOUT, 1);
+        assert!(!sample.success);
+        assert_eq!(sample.status_code, Some(503));
+        assert!(sample.latency_ms.is_some());
+        assert_eq!(sample.error.as_deref(), Some("HTTP 503"));
+    }
+
+    #[test]
+    fn history_tracks_loss_and_failures() {
+        let mut status = ProbeStatus::new(&target(ProbeKind::Tcp, "127.0.0.1:1".to_string()));
+        let sample = |success: bool| ProbeSample {
+            timestamp: 0,
+            success,
+            latency_ms: success.then_some(10.0),
+            status_code: None,
+            error: None,
+        };
+        status.record(sample(true));
+        status.record(sample(false));
+        status.record(sample(false));
+        status.record(sample(true));
+        assert_eq!(status.loss_percent, 50.0);
+        assert_eq!(status.avg_latency_ms, Some(10.0));
+        assert_eq!(status.consecutive_failures, 0);
+    }
+}
@@ -0,0 +1,229 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+
+// PSI（Pressure Stall Information）所在目录，需要 Linux 4.20+ 且开启 CONFIG_PSI
+pub const PSI_ROOT: &str = "/proc/pressure";
+
+// 压力历史：每 10 秒记录一次 avg10，保留最近 1 小时
+const HISTORY_INTERVAL_SECS: i64 = 10;
+const HISTORY_WINDOW_SECS: i64 = 3600;
+
+#[derive(Serialize, Clone, Default)]
+pub struct PressureStats {
+    // 内核不支持 PSI 时为 false，各项均为 None
+    pub available: bool,
+    pub cpu: Option<Pressure>,
+    pub memory: Option<Pressure>,
+    pub io: Option<Pressure>,
+    // 最近 1 小时的 avg10 历史，按时间升序
+    pub history: Vec<PressureSample>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Pressure {
+    // 至少有一个任务因该资源停顿的时间占比
+    pub some: PressureLine,
+    // 所有非空闲任务同时停顿的时间占比（旧内核的 cpu 文件没有这一行）
+    pub full: Option<PressureLine>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct PressureLine {
+    // 10 秒 / 60 秒 / 300 秒滑动平均（百分比）
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    // 累计停顿时间（微秒）
+    pub total_us: u64,
+}
+
+/// 历史中的一个点，各项为对应压力文件的 avg10（百分比）
+#[derive(Serialize, Clone)]
+pub struct PressureSample {
+    pub timestamp: i64,
+    pub cpu_some: Option<f64>,
+    pub memory_some: Option<f64>,
+    pub memory_full: Option<f64>,
+    pub io_some: Option<f64>,
+    pub io_full: Option<f64>,
+}
+
+/// 压力历史，跨采样周期保存在采样器中
+pub struct PressureHistory {
+    samples: VecDeque<PressureSample>,
+}
+
+impl PressureHistory {
+    pub fn new() -> Self {
+        Self {
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// 记录本次采样（距上一个点不足记录间隔时跳过），并填入 stats.history
+    pub fn record(&mut self, now: i64, stats: &mut PressureStats) {
+        if !stats.available {
+            return;
+        }
+        if self
+            .samples
+            .back()
+            .is_none_or(|s| now - s.timestamp >= HISTORY_INTERVAL_SECS)
+        {
+            let some = |p: &Option<Pressure>| p.as_ref().map(|p| p.some.avg10);
+            let full =
+                |p: &Option<Pressure>| p.as_ref().and_then(|p| p.full.as_ref()).map(|l| l.avg10);
+            self.samples.push_back(PressureSample {
+                timestamp: now,
+                cpu_some: some(&stats.cpu),
+                memory_some: some(&stats.memory),
+                memory_full: full(&stats.memory),
+                io_some: some(&stats.io),
+                io_full: full(&stats.io),
+            });
+            while self
+                .samples
+                .front()
+                .is_some_and(|s| now - s.timestamp > HISTORY_WINDOW_SECS)
+            {
+                self.samples.pop_front();
+            }
+        }
+        stats.history = self.samples.iter().cloned().collect();
+    }
+}
+
+/// 读取 cpu / memory / io 三个压力文件
+pub fn collect_pressure(root: &Path) -> PressureStats {
+    let read = |name: &str| {
+        fs::read_to_string(root.join(name))
+            .ok()
+            .and_then(|content| parse_pressure(&content))
+    };
+
+    let cpu = read("cpu");
+    let memory = read("memory");
+    let io = read("io");
+
+    PressureStats {
+        available: cpu.is_some() || memory.is_some() || io.is_some(),
+        cpu,
+        memory,
+        io,
+        history: Vec::new(),
+    }
+}
+
+/// 解析单个压力文件，格式：
+/// `some avg10=0.00 avg60=0.00 avg300=0.00 total=0`
+/// `full avg10=0.00 avg60=0.00 avg300=0.00 total=0`
+pub fn parse_pressure(content: &str) -> Option<Pressure> {
+    let mut some = None;
+    let mut full = None;
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let kind = parts.next();
+        let parsed = parse_line(parts);
+        match kind {
+            Some("some") => some = parsed,
+            Some("full") => full = parsed,
+            _ => {}
+        }
+    }
+
+    Some(Pressure { some: some?, full })
+}
+
+fn parse_line<'a>(fields: impl Iterator<Item = &'a str>) -> Option<PressureLine> {
+    let mut avg10 = None;
+    let mut avg60 = None;
+    let mut avg300 = None;
+    let mut total_us = None;
+
+    for field in fields {
+        let (key, value) = field.split_once('=')?;
+        match key {
+            "avg10" => avg10 = value.parse().ok(),
+            "avg60" => avg60 = value.parse().ok(),
+            "avg300" => avg300 = value.parse().ok(),
+            "total" => total_us = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    Some(PressureLine {
+        avg10: avg10?,
+        avg60: avg60?,
+        avg300: avg300?,
+        total_us: total_us?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEMORY: &str = "\
+some avg10=12.50 avg60=3.10 avg300=0.75 total=98765
+full avg10=4.00 avg60=1.02 avg300=0.20 total=4321
+";
+
+    #[test]
+    fn parses_some_and_full() {
+        let pressure = parse_pressure(MEMORY).unwrap();
+        assert_eq!(pressure.some.avg10, 12.5);
+        assert_eq!(pressure.some.avg300, 0.75);
+        assert_eq!(pressure.some.total_us, 98765);
+        let full = pressure.full.unwrap();
+        assert_eq!(full.avg60, 1.02);
+        assert_eq!(full.total_us, 4321);
+    }
+
+    #[test]
+    fn cpu_file_without_full_line() {
+        let pressure =
+            parse_pressure("some avg10=0.00 avg60=0.10 avg300=0.05 total=1200\n").unwrap();
+        assert_eq!(pressure.some.avg60, 0.1);
+        assert!(pressure.full.is_none());
+    }
+
+    #[test]
+    fn rejects_missing_or_malformed_some() {
+        assert!(parse_pressure("").is_none());
+        assert!(parse_pressure("full avg10=1.00 avg60=0.00 avg300=0.00 total=1\n").is_none());
+        assert!(parse_pressure("some avg10=1.00 avg60=0.00 total=1\n").is_none());
+    }
+
+    #[test]
+    fn history_keeps_interval_and_window() {
+        let mut history = PressureHistory::new();
+        let mut stats = PressureStats {
+            available: true,
+            memory: parse_pressure(MEMORY),
+            ..Default::default()
+        };
+        history.record(0, &mut stats);
+        history.record(4, &mut stats);
+        assert_eq!(stats.history.len(), 1);
+        assert_eq!(stats.history[0].memory_full, Some(4.0));
+        assert_eq!(stats.history[0].io_some, None);
+
+        for now in (10..=HISTORY_WINDOW_SECS + 20).step_by(10) {
+            history.record(now, &mut stats);
+        }
+        let first = stats.history.first().unwrap().timestamp;
+        let last = stats.history.last().unwrap().timestamp;
+        assert_eq!(last - first, HISTORY_WINDOW_SECS);
+    }
+
+    #[test]
+    fn history_skips_kernels_without_psi() {
+        let mut history = PressureHistory::new();
+        let mut stats = PressureStats::default();
+        history.record(0, &mut stats);
+        assert!(stats.history.is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::collector::{self, SystemStats};
+use crate::forecast::DiskHistory;
+use crate::gpu::{self, DrmClientReader, DrmClientSnapshot, PcieThroughputCache};
+use crate::memory::VmStat;
+use crate::network::{NetDevSnapshot, WirelessLinkCache};
+use crate::oom::OomTracker;
+use crate::power::{EnergyTracker, RaplCounter};
+use crate::power_supply::ChargeHistory;
+use crate::probes::{self, ProbeConfig, ProbeResults};
+use crate::psi::PressureHistory;
+use crate::smart::{self, SmartCache, SmartConfig};
+use crate::storage::{DiskFilter, DiskStatsSnapshot, ReadOnlyTracker};
+use crate::storage_health;
+use nvml_wrapper::Nvml;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use sysinfo::System;
+
+// 后台采样间隔（与前端刷新间隔一致）
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 最近一次采样结果，供 HTTP 处理器读取
+pub type LatestStats = Arc<RwLock<Option<SystemStats>>>;
+
+/// 采样配置，启动时从环境变量读取
+pub struct SamplerConfig {
+    pub disk_filter: DiskFilter,
+    pub smart: SmartConfig,
+    // 是否显示虚拟网络接口（lo、docker0、veth 等），MONITOR_NET_INCLUDE_VIRTUAL=1 开启
+    pub include_virtual_interfaces: bool,
+    pub probes: ProbeConfig,
+    // DRM sysfs 根目录（MONITOR_DRM_ROOT，默认 /sys/class/drm），可指向测试用的目录树
+    pub drm_root: PathBuf,
+}
+
+impl SamplerConfig {
+    pub fn from_env() -> Self {
+        Self {
+            disk_filter: DiskFilter::from_env(),
+            smart: SmartConfig::from_env(),
+            include_virtual_interfaces: std::env::var("MONITOR_NET_INCLUDE_VIRTUAL")
+                .is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true")),
+            probes: ProbeConfig::from_env(),
+            drm_root: std::env::var("MONITOR_DRM_ROOT")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from(gpu::DRM_SYSFS_ROOT)),
+        }
+    }
+}
+
+/// 采样器：保存需要跨采样周期的状态
+/// 计数器类指标（分页、网络、磁盘 IO 等）都要靠两次采样的差值计算速率
+pub struct Sampler {
+    pub config: SamplerConfig,
+    pub sys: System,
+    // 上次的 /proc/vmstat 快照及采样时间
+    pub last_vmstat: Option<(VmStat, Instant)>,
+    // 上次的 /proc/diskstats 快照及采样时间
+    pub last_diskstats: Option<(DiskStatsSnapshot, Instant)>,
+    // 上次的 /proc/net/dev 快照及采样时间
+    pub last_net_dev: Option<(NetDevSnapshot, Instant)>,
+    // 无线网卡的 SSID / 速率（iw 查询结果缓存）
+    pub wireless_links: WirelessLinkCache,
+    // DRM 客户端读取（缓存各进程的 DRM 描述符）
+    pub drm_clients: DrmClientReader,
+    // 上次的 DRM fdinfo 快照及采样时间（GPU 进程占用率）
+    pub last_drm_clients: Option<(DrmClientSnapshot, Instant)>,
+    // 上次的 RAPL 能量计数及采样时间
+    pub last_rapl: Option<(Vec<RaplCounter>, Instant)>,
+    // 每日用电量累计
+    pub energy: EnergyTracker,
+    // 电池电量历史（估算续航）
+    pub battery_history: ChargeHistory,
+    // 资源压力（PSI）历史
+    pub pressure_history: PressureHistory,
+    // OOM 计数基准和最近事件
+    pub oom: OomTracker,
+    // 各挂载点的使用量历史（写满预测）
+    pub disk_history: DiskHistory,
+    // 各挂载点的只读状态（检测运行期间被 remount 为只读）
+    pub read_only: ReadOnlyTracker,
+    // SMART 检查结果（由单独的低频线程更新）
+    pub smart: SmartCache,
+    // 主动探测结果（由单独的探测线程更新）
+    pub probes: ProbeResults,
+    // NVML 句柄，启动时初始化一次，与 PCIe 吞吐线程共享；没有 NVIDIA 驱动时为 None
+    pub nvml: Option<Arc<Nvml>>,
+    // NVIDIA GPU 的 PCIe 吞吐（由单独线程更新）
+    pub pcie_throughput: PcieThroughputCache,
+}
+
+impl Sampler {
+    pub fn new(config: SamplerConfig) -> Self {
+        let mut sys = System::new_all();
+        // CPU 占用率需要两次刷新之间的时间间隔
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        sys.refresh_cpu();
+
+        let smart = smart::spawn(&config.smart, Path::new(storage_health::BLOCK_SYSFS_ROOT));
+        let probes = probes::spawn(&config.probes);
+        let nvml = Nvml::init().ok().map(Arc::new);
+        let pcie_throughput = gpu::spawn_pcie_throughput(nvml.clone());
+
+        Self {
+            config,
+            sys,
+            last_vmstat: None,
+            last_diskstats: None,
+            last_net_dev: None,
+            wireless_links: WirelessLinkCache::new(),
+            drm_clients: DrmClientReader::new(Path::new(gpu::PROC_ROOT)),
+            last_drm_clients: None,
+            last_rapl: None,
+            energy: EnergyTracker::new(),
+            battery_history: ChargeHistory::new(),
+            pressure_history: PressureHistory::new(),
+            oom: OomTracker::new(),
+            disk_history: DiskHistory::new(),
+            read_only: ReadOnlyTracker::new(),
+            smart,
+            probes,
+            nvml,
+            pcie_throughput,
+        }
+    }
+}
+
+/// 启动后台采样线程
+/// 采集过程包含大量阻塞的文件读取，因此使用独立线程而不是 tokio 任务
+pub fn spawn(config: SamplerConfig) -> LatestStats {
+    let latest: LatestStats = Arc::new(RwLock::new(None));
+    let writer = latest.clone();
+
+    std::thread::spawn(move || {
+        let mut sampler = Sampler::new(config);
+        loop {
+            let stats = collector::collect_stats(&mut sampler);
+            if let Ok(mut slot) = writer.write() {
+                *slot = Some(stats);
+            }
+            std::thread::sleep(SAMPLE_INTERVAL);
+        }
+    });
+
+    latest
+}
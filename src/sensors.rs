@@ -0,0 +1,466 @@
+use crate::sysfs::{indexed_entries, read_i64, read_string, read_u64};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+pub const HWMON_SYSFS_ROOT: &str = "/sys/class/hwmon";
+pub const THERMAL_SYSFS_ROOT: &str = "/sys/class/thermal";
+
+#[derive(Serialize, Clone)]
+pub struct HardwareSensors {
+    // CPU 温度
+    pub cpu_temp_celsius: Option<f32>,
+    // 主板温度
+    pub motherboard_temp_celsius: Option<f32>,
+    // CPU 风扇转速
+    pub cpu_fan_rpm: Option<u32>,
+    // CPU 电压
+    pub cpu_voltage: Option<f32>,
+    // 所有 hwmon 芯片及其全部通道
+    pub chips: Vec<SensorChip>,
+    // 内核热管理的温区和散热设备（无风扇机器、ARM 板卡通常只有这些）
+    pub thermal_zones: Vec<ThermalZone>,
+    pub cooling_devices: Vec<CoolingDevice>,
+}
+
+/// /sys/class/thermal/thermal_zoneN
+#[derive(Serialize, Clone)]
+pub struct ThermalZone {
+    // 目录名，如 thermal_zone0
+    pub name: String,
+    // 温区类型，如 x86_pkg_temp、acpitz、cpu-thermal
+    pub zone_type: String,
+    pub temperature_celsius: Option<f64>,
+    pub trip_points: Vec<TripPoint>,
+}
+
+/// 温区触发点：达到 passive 时降频，active 时启动风扇，hot / critical 时关机或休眠
+#[derive(Serialize, Clone)]
+pub struct TripPoint {
+    // passive / active / hot / critical
+    pub trip_type: String,
+    pub temperature_celsius: f64,
+}
+
+/// /sys/class/thermal/cooling_deviceN，如风扇、CPU 降频（Processor）、intel_powerclamp
+#[derive(Serialize, Clone)]
+pub struct CoolingDevice {
+    pub name: String,
+    pub device_type: String,
+    // 当前档位和最大档位，0 表示未启用
+    pub cur_state: Option<u64>,
+    pub max_state: Option<u64>,
+}
+
+/// 一个 hwmon 芯片，如 coretemp、nct6798、nvme、amdgpu
+#[derive(Serialize, Clone)]
+pub struct SensorChip {
+    pub name: String,
+    // hwmon 目录名（如 hwmon3），同名芯片（多块 NVMe）以此区分
+    pub hwmon: String,
+    pub readings: Vec<SensorReading>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SensorKind {
+    Temperature,
+    Fan,
+    Voltage,
+    Current,
+    Power,
+}
+
+/// 单个通道的读数，已换算为 °C / RPM / V / A / W
+#[derive(Serialize, Clone)]
+pub struct SensorReading {
+    pub kind: SensorKind,
+    // 通道名，如 temp1、fan2、in0
+    pub channel: String,
+    // 驱动提供的标签，如 `Package id 0`、`Tctl`、`Composite`
+    pub label: Option<String>,
+    pub value: f64,
+    pub max: Option<f64>,
+    pub crit: Option<f64>,
+}
+
+// 通道前缀、类型和 sysfs 数值到显示单位的除数
+// （温度为毫摄氏度，电压毫伏，电流毫安，功率微瓦，风扇为 RPM）
+const CHANNEL_TYPES: &[(&str, SensorKind, f64)] = &[
+    ("temp", SensorKind::Temperature, 1000.0),
+    ("fan", SensorKind::Fan, 1.0),
+    ("in", SensorKind::Voltage, 1000.0),
+    ("curr", SensorKind::Current, 1000.0),
+    ("power", SensorKind::Power, 1_000_000.0),
+];
+
+// 主板超级 I/O 芯片（nct6775、it87、w83627 等）的名称前缀，通道通常没有标签
+const SUPER_IO_PREFIXES: &[&str] = &["nct", "it8", "w83"];
+
+/// 采集所有 hwmon 芯片和温区，并从中挑出 CPU / 主板温度等汇总值
+pub fn collect_hardware_sensors(hwmon_root: &Path, thermal_root: &Path) -> HardwareSensors {
+    let chips = collect_chips(hwmon_root);
+    let thermal_zones = collect_thermal_zones(thermal_root);
+    let zone_temp = |types: &[&str]| {
+        thermal_zones
+            .iter()
+            .filter(|z| types.contains(&z.zone_type.as_str()))
+            .find_map(|z| z.temperature_celsius)
+            .map(|t| t as f32)
+    };
+
+    // CPU 温度：Intel 取 `Package id 0`，AMD 取 Tctl / Tdie，没有标签时取 temp1
+    let cpu_temp_celsius = chips
+        .iter()
+        .filter(|c| {
+            matches!(
+                c.name.as_str(),
+                "coretemp" | "k10temp" | "zenpower" | "cpu_thermal"
+            )
+        })
+        .find_map(|chip| {
+            let temps = || {
+                chip.readings
+                    .iter()
+                    .filter(|r| r.kind == SensorKind::Temperature)
+            };
+            temps()
+                .find(|r| {
+                    r.label
+                        .as_deref()
+                        .is_some_and(|l| l.starts_with("Package") || l == "Tctl" || l == "Tdie")
+                })
+                .or_else(|| temps().next())
+        })
+        .map(|r| r.value as f32)
+        // 没有 CPU 温度芯片时退回 CPU 温区；其他温区（acpitz、电池、无线网卡等）不是 CPU 温度
+        .or_else(|| zone_temp(&["x86_pkg_temp", "cpu-thermal", "cpu_thermal", "soc_thermal"]));
+
+    let motherboard_temp_celsius = chips
+        .iter()
+        .filter(|c| c.name.contains("acpitz") || c.name.contains("board"))
+        .find_map(|chip| {
+            chip.readings
+                .iter()
+                .find(|r| r.kind == SensorKind::Temperature)
+        })
+        .map(|r| r.value as f32)
+        .or_else(|| zone_temp(&["acpitz"]));
+
+    // 风扇：优先取标签含 CPU 的通道，否则取超级 I/O 芯片上第一个在转的风扇
+    // （按惯例 CPU 风扇接第一个插座；显卡、笔记本 EC 的风扇不是 CPU 风扇）
+    let fans = || {
+        chips
+            .iter()
+            .flat_map(|c| c.readings.iter().map(move |r| (c, r)))
+            .filter(|(_, r)| r.kind == SensorKind::Fan && r.value > 0.0)
+    };
+    let cpu_fan_rpm = fans()
+        .find(|(_, r)| label_contains(r, "cpu"))
+        .or_else(|| fans().find(|(c, _)| is_super_io(c)))
+        .map(|(_, r)| r.value as u32);
+
+    // 电压：优先取标签为 Vcore / CPU 的通道；
+    // 超级 I/O 芯片没有标签，按惯例 in0 为 Vcore
+    let voltages = || {
+        chips
+            .iter()
+            .flat_map(|c| c.readings.iter().map(move |r| (c, r)))
+            .filter(|(_, r)| r.kind == SensorKind::Voltage)
+    };
+    let cpu_voltage = voltages()
+        .find(|(_, r)| label_contains(r, "vcore") || label_contains(r, "cpu"))
+        .or_else(|| voltages().find(|(c, r)| is_super_io(c) && r.channel == "in0"))
+        .map(|(_, r)| r.value as f32);
+
+    HardwareSensors {
+        cpu_temp_celsius,
+        motherboard_temp_celsius,
+        cpu_fan_rpm,
+        cpu_voltage,
+        chips,
+        thermal_zones,
+        cooling_devices: collect_cooling_devices(thermal_root),
+    }
+}
+
+/// 遍历所有温区及其 trip_point_N_type / trip_point_N_temp
+pub fn collect_thermal_zones(thermal_root: &Path) -> Vec<ThermalZone> {
+    indexed_entries(thermal_root, "thermal_zone")
+        .into_iter()
+        .filter_map(|(_, path)| {
+            let trip_points = (0..)
+                .map_while(|n| {
+                    let trip_type = read_string(path.join(format!("trip_point_{}_type", n)))?;
+                    Some((
+                        trip_type,
+                        read_i64(path.join(format!("trip_point_{}_temp", n))),
+                    ))
+                })
+                // 未配置的触发点温度为 0 或负数
+                .filter_map(|(trip_type, temp)| {
+                    Some(TripPoint {
+                        trip_type,
+                        temperature_celsius: temp.filter(|t| *t > 0)? as f64 / 1000.0,
+                    })
+                })
+                .collect();
+            Some(ThermalZone {
+                name: path.file_name()?.to_string_lossy().into_owned(),
+                zone_type: read_string(path.join("type")).unwrap_or_default(),
+                // 部分温区（如未接传感器的 ACPI 温区）读取会失败
+                temperature_celsius: read_i64(path.join("temp")).map(|t| t as f64 / 1000.0),
+                trip_points,
+            })
+        })
+        .collect()
+}
+
+pub fn collect_cooling_devices(thermal_root: &Path) -> Vec<CoolingDevice> {
+    indexed_entries(thermal_root, "cooling_device")
+        .into_iter()
+        .filter_map(|(_, path)| {
+            Some(CoolingDevice {
+                name: path.file_name()?.to_string_lossy().into_owned(),
+                device_type: read_string(path.join("type")).unwrap_or_default(),
+                cur_state: read_u64(path.join("cur_state")),
+                max_state: read_u64(path.join("max_state")),
+            })
+        })
+        .collect()
+}
+
+fn is_super_io(chip: &SensorChip) -> bool {
+    SUPER_IO_PREFIXES.iter().any(|p| chip.name.starts_with(p))
+}
+
+fn label_contains(reading: &SensorReading, needle: &str) -> bool {
+    reading
+        .label
+        .as_deref()
+        .is_some_and(|l| l.to_lowercase().contains(needle))
+}
+
+/// 遍历 /sys/class/hwmon/hwmon*，按编号排序
+pub fn collect_chips(hwmon_root: &Path) -> Vec<SensorChip> {
+    indexed_entries(hwmon_root, "hwmon")
+        .into_iter()
+        .filter_map(|(_, path)| {
+            let readings = read_channels(&path);
+            if readings.is_empty() {
+                return None;
+            }
+            Some(SensorChip {
+                name: read_string(path.join("name")).unwrap_or_default(),
+                hwmon: path.file_name()?.to_string_lossy().into_owned(),
+                readings,
+            })
+        })
+        .collect()
+}
+
+/// 读取芯片目录下所有 `<类型><N>_input` 通道（功率通道可能只有 `_average`），
+/// 以及对应的 `_label`、`_max`、`_crit`
+fn read_channels(chip: &Path) -> Vec<SensorReading> {
+    let Ok(entries) = fs::read_dir(chip) else {
+        return Vec::new();
+    };
+    let files: Vec<String> = entries
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+
+    let mut channels: Vec<(usize, usize, String, &str)> = files
+        .iter()
+        .filter_map(|file| {
+            let (channel, suffix) = file.split_once('_')?;
+            let has_input = files.iter().any(|f| *f == format!("{}_input", channel));
+            let value_file = match suffix {
+                "input" => "input",
+                "average" if !has_input => "average",
+                _ => return None,
+            };
+            let (order, number) =
+                CHANNEL_TYPES
+                    .iter()
+                    .enumerate()
+                    .find_map(|(order, (prefix, _, _))| {
+                        let number = channel.strip_prefix(prefix)?.parse::<usize>().ok()?;
+                        Some((order, number))
+                    })?;
+            Some((order, number, channel.to_string(), value_file))
+        })
+        .collect();
+    channels.sort();
+
+    channels
+        .into_iter()
+        .filter_map(|(order, _, channel, value_file)| {
+            let (_, kind, divisor) = CHANNEL_TYPES[order];
+            let value = |suffix: &str| {
+                read_i64(chip.join(format!("{}_{}", channel, suffix))).map(|v| v as f64 / divisor)
+            };
+            Some(SensorReading {
+                kind,
+                label: read_string(chip.join(format!("{}_label", channel))),
+                value: value(value_file)?,
+                max: value("max"),
+                crit: value("crit"),
+                channel,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::FixtureTree;
+
+    #[test]
+    fn reads_hwmon_channels() {
+        let tree = FixtureTree::new("hwmon-chips");
+        tree.write("hwmon/hwmon0/name", "coretemp\n")
+            .write("hwmon/hwmon0/temp1_input", "52000\n")
+            .write("hwmon/hwmon0/temp1_label", "Package id 0\n")
+            .write("hwmon/hwmon0/temp1_max", "100000\n")
+            .write("hwmon/hwmon0/temp1_crit", "105000\n")
+            .write("hwmon/hwmon0/temp2_input", "48000\n")
+            .write("hwmon/hwmon0/temp2_label", "Core 0\n")
+            // amdgpu 旧内核只有 power1_average
+            .write("hwmon/hwmon10/name", "amdgpu\n")
+            .write("hwmon/hwmon10/power1_average", "35500000\n")
+            .write("hwmon/hwmon10/in0_input", "906\n")
+            .write("hwmon/hwmon10/fan1_input", "0\n")
+            // 没有任何通道的芯片不显示
+            .write("hwmon/hwmon2/name", "acpi_fan\n");
+
+        let chips = collect_chips(&tree.root.join("hwmon"));
+        let names: Vec<_> = chips.iter().map(|c| (&*c.name, &*c.hwmon)).collect();
+        assert_eq!(names, vec![("coretemp", "hwmon0"), ("amdgpu", "hwmon10")]);
+
+        let package = &chips[0].readings[0];
+        assert_eq!(package.channel, "temp1");
+        assert_eq!(package.label.as_deref(), Some("Package id 0"));
+        assert_eq!(package.value, 52.0);
+        assert_eq!(package.max, Some(100.0));
+        assert_eq!(package.crit, Some(105.0));
+
+        // 按温度、风扇、电压、电流、功率排序
+        let amdgpu: Vec<_> = chips[1]
+            .readings
+            .iter()
+            .map(|r| (&*r.channel, r.value))
+            .collect();
+        assert_eq!(
+            amdgpu,
+            vec![("fan1", 0.0), ("in0", 0.906), ("power1", 35.5)]
+        );
+    }
+
+    fn fan_chip(tree: &FixtureTree, hwmon: &str, name: &str, rpm: &str, label: Option<&str>) {
+        tree.write(&format!("hwmon/{}/name", hwmon), name)
+            .write(&format!("hwmon/{}/fan1_input", hwmon), rpm);
+        if let Some(label) = label {
+            tree.write(&format!("hwmon/{}/fan1_label", hwmon), label);
+        }
+    }
+
+    fn cpu_fan(tree: &FixtureTree) -> Option<u32> {
+        collect_hardware_sensors(&tree.root.join("hwmon"), &tree.root.join("thermal")).cpu_fan_rpm
+    }
+
+    #[test]
+    fn cpu_fan_prefers_cpu_label() {
+        let tree = FixtureTree::new("hwmon-fan-label");
+        fan_chip(&tree, "hwmon0", "nct6798", "900", None);
+        fan_chip(&tree, "hwmon1", "thinkpad", "2400", Some("CPU Fan"));
+        assert_eq!(cpu_fan(&tree), Some(2400));
+    }
+
+    #[test]
+    fn cpu_fan_falls_back_to_super_io_only() {
+        let tree = FixtureTree::new("hwmon-fan-gpu");
+        // 显卡风扇不能当作 CPU 风扇
+        fan_chip(&tree, "hwmon0", "amdgpu", "1500", None);
+        assert_eq!(cpu_fan(&tree), None);
+
+        // 停转的风扇跳过
+        fan_chip(&tree, "hwmon1", "it8688", "0", None);
+        assert_eq!(cpu_fan(&tree), None);
+        fan_chip(&tree, "hwmon2", "nct6775", "1100", None);
+        assert_eq!(cpu_fan(&tree), Some(1100));
+    }
+
+    #[test]
+    fn cpu_voltage_uses_super_io_in0() {
+        let tree = FixtureTree::new("hwmon-vcore");
+        tree.write("hwmon/hwmon0/name", "amdgpu\n")
+            .write("hwmon/hwmon0/in0_input", "900\n")
+            .write("hwmon/hwmon1/name", "nct6798\n")
+            .write("hwmon/hwmon1/in0_input", "1248\n")
+            .write("hwmon/hwmon1/in1_input", "1000\n");
+        let sensors =
+            collect_hardware_sensors(&tree.root.join("hwmon"), &tree.root.join("thermal"));
+        assert_eq!(sensors.cpu_voltage, Some(1.248));
+    }
+
+    fn zone(tree: &FixtureTree, n: u32, zone_type: &str, temp: &str) {
+        tree.write(&format!("thermal/thermal_zone{}/type", n), zone_type)
+            .write(&format!("thermal/thermal_zone{}/temp", n), temp);
+    }
+
+    #[test]
+    fn reads_thermal_zones_and_trip_points() {
+        let tree = FixtureTree::new("thermal-zones");
+        zone(&tree, 0, "acpitz\n", "27800\n");
+        tree.write("thermal/thermal_zone0/trip_point_0_type", "critical\n")
+            .write("thermal/thermal_zone0/trip_point_0_temp", "119000\n")
+            // 未配置的触发点
+            .write("thermal/thermal_zone0/trip_point_1_type", "passive\n")
+            .write("thermal/thermal_zone0/trip_point_1_temp", "0\n")
+            .write("thermal/thermal_zone0/trip_point_2_type", "active\n")
+            .write("thermal/thermal_zone0/trip_point_2_temp", "70000\n")
+            // 读取失败的温区
+            .write("thermal/thermal_zone1/type", "INT3400 Thermal\n")
+            .write("thermal/cooling_device0/type", "Processor\n")
+            .write("thermal/cooling_device0/cur_state", "0\n")
+            .write("thermal/cooling_device0/max_state", "3\n");
+
+        let zones = collect_thermal_zones(&tree.root.join("thermal"));
+        assert_eq!(zones.len(), 2);
+        assert_eq!(zones[0].zone_type, "acpitz");
+        assert_eq!(zones[0].temperature_celsius, Some(27.8));
+        let trips: Vec<_> = zones[0]
+            .trip_points
+            .iter()
+            .map(|t| (&*t.trip_type, t.temperature_celsius))
+            .collect();
+        assert_eq!(trips, vec![("critical", 119.0), ("active", 70.0)]);
+        assert_eq!(zones[1].temperature_celsius, None);
+
+        let devices = collect_cooling_devices(&tree.root.join("thermal"));
+        assert_eq!(devices[0].device_type, "Processor");
+        assert_eq!(devices[0].max_state, Some(3));
+    }
+
+    #[test]
+    fn cpu_temp_falls_back_to_cpu_zones_only() {
+        let tree = FixtureTree::new("thermal-cpu");
+        zone(&tree, 0, "acpitz\n", "40000\n");
+        zone(&tree, 1, "iwlwifi_1\n", "45000\n");
+        let sensors =
+            || collect_hardware_sensors(&tree.root.join("hwmon"), &tree.root.join("thermal"));
+        assert_eq!(sensors().cpu_temp_celsius, None);
+        assert_eq!(sensors().motherboard_temp_celsius, Some(40.0));
+
+        zone(&tree, 2, "x86_pkg_temp\n", "61000\n");
+        assert_eq!(sensors().cpu_temp_celsius, Some(61.0));
+
+        // 有 CPU 温度芯片时优先使用芯片读数
+        tree.write("hwmon/hwmon0/name", "k10temp\n")
+            .write("hwmon/hwmon0/temp1_input", "70125\n")
+            .write("hwmon/hwmon0/temp1_label", "Tctl\n");
+        assert_eq!(sensors().cpu_temp_celsius, Some(70.125));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::command;
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+pub const CLASS_BLOCK_ROOT: &str = "/sys/class/block";
+
+// 默认每 30 分钟检查一次（即使跳过休眠的硬盘，读取 SMART 日志也有开销）
+const DEFAULT_SMART_INTERVAL_SECS: u64 = 1800;
+// 单块磁盘的检查超时，故障磁盘可能让 smartctl 长时间卡在 IO 上
+const SMARTCTL_TIMEOUT: Duration = Duration::from_secs(30);
+// 不检查的虚拟块设备
+const SKIPPED_DEVICE_PREFIXES: &[&str] = &["loop", "ram", "zram", "dm-", "md", "sr", "nbd"];
+
+#[derive(Serialize, Clone)]
+pub struct SmartInfo {
+    // 整盘设备名，如 sda / nvme0n1
+    pub device: String,
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    // SMART 总体健康评估，smartctl 无法判断时为 None
+    pub health_passed: Option<bool>,
+    pub temperature_celsius: Option<i64>,
+    pub power_on_hours: Option<u64>,
+    // ATA 属性 5 / 197：已重映射 / 待重映射扇区
+    pub reallocated_sectors: Option<u64>,
+    pub pending_sectors: Option<u64>,
+    // NVMe 寿命消耗百分比（可超过 100）、剩余备用块、介质错误数
+    pub nvme_percentage_used: Option<u64>,
+    pub nvme_available_spare: Option<u64>,
+    pub media_errors: Option<u64>,
+    // 检查时间（Unix 秒）
+    pub checked_at: i64,
+}
+
+/// SMART 检查配置
+/// - MONITOR_SMARTCTL：smartctl 路径，设置后才启用（如 /usr/sbin/smartctl）
+/// - MONITOR_SMART_INTERVAL_SECS：检查间隔，默认 1800 秒
+#[derive(Clone)]
+pub struct SmartConfig {
+    pub smartctl: Option<PathBuf>,
+    pub interval: Duration,
+}
+
+impl SmartConfig {
+    pub fn from_env() -> Self {
+        Self {
+            smartctl: std::env::var("MONITOR_SMARTCTL").ok().map(PathBuf::from),
+            interval: Duration::from_secs(
+                std::env::var("MONITOR_SMART_INTERVAL_SECS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(DEFAULT_SMART_INTERVAL_SECS),
+            ),
+        }
+    }
+}
+
+/// 最近一次 SMART 检查结果：整盘设备名 -> 信息
+pub type SmartCache = Arc<RwLock<HashMap<String, SmartInfo>>>;
+
+/// 启动 SMART 检查线程；未配置 smartctl 时返回空缓存
+pub fn spawn(config: &SmartConfig, block_root: &Path) -> SmartCache {
+    let cache: SmartCache = Arc::new(RwLock::new(HashMap::new()));
+    let Some(smartctl) = config.smartctl.clone() else {
+        return cache;
+    };
+
+    let writer = cache.clone();
+    let interval = config.interval;
+    let block_root = block_root.to_path_buf();
+    std::thread::spawn(move || {
+        loop {
+            // 处于休眠、本次超时的磁盘沿用上次的结果
+            let previous = writer.read().map(|c| c.clone()).unwrap_or_default();
+            let results: HashMap<String, SmartInfo> = list_disks(&block_root)
+                .into_iter()
+                .filter_map(|device| {
+                    let info = run_smartctl(&smartctl, &device)
+                        .or_else(|| previous.get(&device).cloned())?;
+                    Some((device, info))
+                })
+                .collect();
+            if let Ok(mut slot) = writer.write() {
+                *slot = results;
+            }
+            std::thread::sleep(interval);
+        }
+    });
+
+    cache
+}
+
+/// 列出需要检查的物理磁盘（/sys/block 下的整盘设备）
+fn list_disks(block_root: &Path) -> Vec<String> {
+    let mut disks: Vec<String> = fs::read_dir(block_root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| !SKIPPED_DEVICE_PREFIXES.iter().any(|p| name.starts_with(p)))
+                .collect()
+        })
+        .unwrap_or_default();
+    disks.sort();
+    disks
+}
+
+fn run_smartctl(smartctl: &Path, device: &str) -> Option<SmartInfo> {
+    // -n standby：磁盘处于休眠时不唤醒，直接退出
+    // smartctl 的退出码是位掩码，磁盘有问题时也会非 0，因此只看输出能否解析
+    let output = command::output_with_timeout(
+        Command::new(smartctl).args(["--json", "-n", "standby", "-a", &format!("/dev/{}", device)]),
+        SMARTCTL_TIMEOUT,
+    )?;
+    parse_smartctl_json(device, &String::from_utf8_lossy(&output))
+}
+
+/// 解析 `smartctl --json -a` 的输出（ATA 和 NVMe 字段不同，缺失的字段为 None）
+pub fn parse_smartctl_json(device: &str, content: &str) -> Option<SmartInfo> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    // 设备无法打开等错误时没有 device 字段
+    json.get("device")?;
+    // 因 -n standby 跳过的休眠磁盘只有设备信息，没有任何 SMART 数据
+    let skipped = json
+        .pointer("/smartctl/messages")
+        .and_then(Value::as_array)
+        .is_some_and(|messages| {
+            messages.iter().any(|m| {
+                m.get("string")
+                    .and_then(Value::as_str)
+                    .is_some_and(|s| s.contains("STANDBY"))
+            })
+        });
+    if skipped {
+        return None;
+    }
+
+    let string = |pointer: &str| json.pointer(pointer)?.as_str().map(|s| s.to_string());
+    let number = |pointer: &str| json.pointer(pointer)?.as_u64();
+
+    // ATA 属性表中按 id 查原始值
+    let ata_raw = |id: u64| {
+        json.pointer("/ata_smart_attributes/table")?
+            .as_array()?
+            .iter()
+            .find(|attr| attr.get("id").and_then(Value::as_u64) == Some(id))?
+            .pointer("/raw/value")?
+            .as_u64()
+    };
+
+    let nvme = "/nvme_smart_health_information_log";
+
+    Some(SmartInfo {
+        device: device.to_string(),
+        model: string("/model_name"),
+        serial: string("/serial_number"),
+        health_passed: json
+            .pointer("/smart_status/passed")
+            .and_then(Value::as_bool),
+        temperature_celsius: json.pointer("/temperature/current").and_then(Value::as_i64),
+        power_on_hours: number("/power_on_time/hours"),
+        reallocated_sectors: ata_raw(5),
+        pending_sectors: ata_raw(197),
+        nvme_percentage_used: number(&format!("{}/percentage_used", nvme)),
+        nvme_available_spare: number(&format!("{}/available_spare", nvme)),
+        media_errors: number(&format!("{}/media_errors", nvme)),
+        checked_at: Utc::now().timestamp(),
+    })
+}
+
+/// 分区对应的整盘设备名，如 sda1 -> sda、nvme0n1p2 -> nvme0n1；本身是整盘时原样返回
+pub fn parent_disk(class_block_root: &Path, device: &str) -> String {
+    let path = class_block_root.join(device);
+    if !path.join("partition").exists() {
+        return device.to_string();
+    }
+    // /sys/class/block/sda1 -> .../block/sda/sda1
+    fs::canonicalize(&path)
+        .ok()
+        .and_then(|p| {
+            p.parent()?
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| device.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ATA: &str = r#"{
+  "json_format_version": [1, 0],
+  "smartctl": {"version": [7, 3], "exit_status": 0},
+  "device": {"name": "/dev/sda", "type": "sat", "protocol": "ATA"},
+  "model_name": "WDC WD40EFRX-68N32N0",
+  "serial_number": "WD-WCC7K1234567",
+  "smart_status": {"passed": true},
+  "ata_smart_attributes": {
+    "revision": 16,
+    "table": [
+      {"id": 5, "name": "Reallocated_Sector_Ct", "value": 200, "raw": {"value": 8, "string": "8"}},
+      {"id": 9, "name": "Power_On_Hours", "value": 45, "raw": {"value": 40321, "string": "40321"}},
+      {"id": 197, "name": "Current_Pending_Sector", "value": 200, "raw": {"value": 0, "string": "0"}}
+    ]
+  },
+  "power_on_time": {"hours": 40321},
+  "temperature": {"current": 34}
+}"#;
+
+    const NVME: &str = r#"{
+  "smartctl": {"version": [7, 3], "exit_status": 0},
+  "device": {"name": "/dev/nvme0", "type": "nvme", "protocol": "NVMe"},
+  "model_name": "Samsung SSD 980 PRO 1TB",
+  "serial_number": "S5GXNF0R123456",
+  "smart_status": {"passed": true, "nvme": {"value": 0}},
+  "nvme_smart_health_information_log": {
+    "critical_warning": 0,
+    "temperature": 41,
+    "available_spare": 100,
+    "percentage_used": 3,
+    "media_errors": 0
+  },
+  "temperature": {"current": 41},
+  "power_on_time": {"hours": 5120}
+}"#;
+
+    #[test]
+    fn parses_ata() {
+        let info = parse_smartctl_json("sda", ATA).unwrap();
+        assert_eq!(info.device, "sda");
+        assert_eq!(info.model.as_deref(), Some("WDC WD40EFRX-68N32N0"));
+        assert_eq!(info.health_passed, Some(true));
+        assert_eq!(info.temperature_celsius, Some(34));
+        assert_eq!(info.power_on_hours, Some(40321));
+        assert_eq!(info.reallocated_sectors, Some(8));
+        assert_eq!(info.pending_sectors, Some(0));
+        assert_eq!(info.nvme_percentage_used, None);
+    }
+
+    #[test]
+    fn parses_nvme() {
+        let info = parse_smartctl_json("nvme0n1", NVME).unwrap();
+        assert_eq!(info.serial.as_deref(), Some("S5GXNF0R123456"));
+        assert_eq!(info.nvme_percentage_used, Some(3));
+        assert_eq!(info.nvme_available_spare, Some(100));
+        assert_eq!(info.media_errors, Some(0));
+        assert_eq!(info.reallocated_sectors, None);
+        assert_eq!(info.power_on_hours, Some(5120));
+    }
+
+    #[test]
+    fn failing_health() {
+        let info = parse_smartctl_json(
+            "sdb",
+            r#"{
+  "smartctl": {"exit_status": 8},
+  "device": {"name": "/dev/sdb", "type": "sat"},
+  "model_name": "ST2000DM001",
+  "smart_status": {"passed": false},
+  "ata_smart_attributes": {"table": [
+    {"id": 5, "raw": {"value": 2048}},
+    {"id": 197, "raw": {"value": 96}}
+  ]}
+}"#,
+        )
+        .unwrap();
+        assert_eq!(info.health_passed, Some(false));
+        assert_eq!(info.reallocated_sectors, Some(2048));
+        assert_eq!(info.pending_sectors, Some(96));
+        assert_eq!(info.temperature_celsius, None);
+    }
+
+    #[test]
+    fn standby_disk_is_skipped() {
+        let content = r#"{
+  "smartctl": {
+    "exit_status": 2,
+    "messages": [{"string": "Device is in STANDBY mode, exit(2)", "severity": "information"}]
+  },
+  "device": {"name": "/dev/sdc", "type": "sat"}
+}"#;
+        assert!(parse_smartctl_json("sdc", content).is_none());
+    }
+
+    #[test]
+    fn unopenable_device_or_garbage() {
+        let content = r#"{"smartctl": {"exit_status": 2, "messages": [{"string": "Smartctl open device: /dev/sdz failed: No such device"}]}}"#;
+        assert!(parse_smartctl_json("sdz", content).is_none());
+        assert!(parse_smartctl_json("sda", "smartctl: command not found").is_none());
+    }
+}
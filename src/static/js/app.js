@@ -4,6 +4,17 @@
 let token = localStorage.getItem('monitor_token');
 let refreshInterval;
 
+// 转义来自外部的字符串（SSID、进程名、挂载点、驱动提供的标签等），
+// 插入 innerHTML 或属性值前都要经过这里
+function escapeHtml(value) {
+    return String(value ?? '')
+        .replace(/&/g, '&amp;')
+        .replace(/</g, '&lt;')
+        .replace(/>/g, '&gt;')
+        .replace(/"/g, '&quot;')
+        .replace(/'/g, '&#39;');
+}
+
 // 初始化
 if (token) {
     showMain();
@@ -79,98 +90,23 @@ function updateUI(data) {
     // CPU
     document.getElementById('cpuValue').textContent = r.cpu_usage.toFixed(1) + '%';
     document.getElementById('cpuBar').style.width = r.cpu_usage + '%';
-    document.getElementById('cpuMeta').textContent = `${r.cpu_name} · ${r.cpu_count}核`;
+    const sockets = r.cpu_sockets > 1 ? `${r.cpu_sockets}路 · ` : '';
+    document.getElementById('cpuMeta').textContent =
+        `${r.cpu_name} · ${sockets}${r.cpu_physical_cores}核${r.cpu_count}线程`;
     
     // 内存
     document.getElementById('memValue').textContent = r.memory_usage_percent.toFixed(1) + '%';
     document.getElementById('memBar').style.width = r.memory_usage_percent + '%';
+    const swap = r.swap_total > 0
+        ? ` · Swap ${r.swap_used.toFixed(2)}GB / ${r.swap_total.toFixed(2)}GB`
+        : '';
     document.getElementById('memMeta').textContent = 
-        `${r.memory_used.toFixed(2)}GB / ${r.memory_total.toFixed(2)}GB`;
+        `${r.memory_used.toFixed(2)}GB / ${r.memory_total.toFixed(2)}GB${swap}`;
     
     // GPU（自动识别）
     const gpuEl = document.getElementById('gpuContent');
-    if (data.gpu) {
-        const g = data.gpu;
-        const vendorIcon = g.vendor === 'NVIDIA' ? '🟢' : 
-                          g.vendor === 'AMD' ? '🔴' : '🔵';
-        
-        let gpuHtml = `
-            <div class="resource-value">${g.usage_percent}%</div>
-            <div class="progress-bar">
-                <div class="progress-fill gpu" style="width: ${g.usage_percent}%"></div>
-            </div>
-            <div class="resource-meta" style="margin-top: 12px;">${vendorIcon} ${g.vendor} - ${g.name}</div>
-            <div class="gpu-details">
-        `;
-        
-        // 显存（如果有）
-        if (g.memory_total_mb > 0) {
-            gpuHtml += `
-                <div class="gpu-detail-item">
-                    <span class="gpu-detail-label">显存</span>
-                    <span class="gpu-detail-value">${(g.memory_used_mb/1024).toFixed(2)} / ${(g.memory_total_mb/1024).toFixed(2)} GB</span>
-                </div>
-            `;
-        }
-        
-        // 温度（如果有）
-        if (g.temperature > 0) {
-            gpuHtml += `
-                <div class="gpu-detail-item">
-                    <span class="gpu-detail-label">温度</span>
-                    <span class="gpu-detail-value">${g.temperature}°C</span>
-                </div>
-            `;
-        }
-        
-        // 风扇转速（如果有）
-        if (g.fan_speed_percent !== null && g.fan_speed_percent !== undefined) {
-            gpuHtml += `
-                <div class="gpu-detail-item">
-                    <span class="gpu-detail-label">风扇</span>
-                    <span class="gpu-detail-value">${g.fan_speed_percent}%</span>
-                </div>
-            `;
-        }
-        
-        // 核心频率（如果有）
-        if (g.core_clock_mhz !== null && g.core_clock_mhz !== undefined) {
-            gpuHtml += `
-                <div class="gpu-detail-item">
-                    <span class="gpu-detail-label">核心频率</span>
-                    <span class="gpu-detail-value">${g.core_clock_mhz} MHz</span>
-                </div>
-            `;
-        }
-        
-        // 显存频率（如果有）
-        if (g.memory_clock_mhz !== null && g.memory_clock_mhz !== undefined) {
-            gpuHtml += `
-                <div class="gpu-detail-item">
-                    <span class="gpu-detail-label">显存频率</span>
-                    <span class="gpu-detail-value">${g.memory_clock_mhz} MHz</span>
-                </div>
-            `;
-        }
-        
-        gpuHtml += '</div>';
-        
-        // 占用显存的进程（如果有）
-        if (g.top_processes && g.top_processes.length > 0) {
-            gpuHtml += `
-                <div class="gpu-processes">
-                    <div class="gpu-process-title">显存占用进程</div>
-                    ${g.top_processes.map(p => `
-                        <div class="gpu-process-item">
-                            <span>${p.name} (PID: ${p.pid})</span>
-                            <span>${p.memory_mb} MB</span>
-                        </div>
-                    `).join('')}
-                </div>
-            `;
-        }
-        
-        gpuEl.innerHTML = gpuHtml;
+    if (data.gpus && data.gpus.length > 0) {
+        gpuEl.innerHTML = data.gpus.map(renderGpu).join('');
     } else {
         gpuEl.innerHTML = '<div class="no-gpu">未检测到 GPU</div>';
     }
@@ -180,11 +116,11 @@ function updateUI(data) {
     if (data.processes && data.processes.length > 0) {
         processEl.innerHTML = data.processes.map(p => `
             <div class="process-item">
-                <div class="process-name">${p.name}</div>
+                <div class="process-name">${escapeHtml(p.name)}</div>
                 <div class="process-stats">
                     <span class="process-cpu">CPU: ${p.cpu_usage.toFixed(1)}%</span>
                     <span class="process-mem">MEM: ${p.memory_mb.toFixed(0)}MB</span>
-                    <span class="process-status ${p.status}">${p.status}</span>
+                    <span class="process-status ${escapeHtml(p.status)}">${escapeHtml(p.status)}</span>
                 </div>
             </div>
         `).join('');
@@ -204,33 +140,48 @@ function updateUI(data) {
             <span class="cpu-info-label">负载均衡</span>
             <span class="cpu-info-value">${ca.load_avg_1.toFixed(2)} / ${ca.load_avg_5.toFixed(2)} / ${ca.load_avg_15.toFixed(2)}</span>
         </div>
+        ${psiRow(data.pressure)}
+        ${cacheRow(ca.topology)}
         <div class="cpu-core-usage">
             <div class="cpu-core-title">各核心占用率</div>
-            <div class="cpu-core-grid">
-                ${ca.per_core_usage.map((usage, i) => `
-                    <div class="cpu-core-item">
-                        <div class="core-label">核心 ${i + 1}</div>
-                        <div class="core-bar">
-                            <div class="core-fill" style="width: ${usage}%"></div>
-                        </div>
-                        <div class="core-value">${usage.toFixed(0)}%</div>
-                    </div>
-                `).join('')}
-            </div>
+            <div class="cpu-core-grid">${cpuCoresHtml(ca)}</div>
         </div>
     `;
     
+    // 告警
+    const alertEl = document.getElementById('alertIndicator');
+    if (data.alerts && data.alerts.length > 0) {
+        const detail = data.alerts.map(a => a.message).join('\n');
+        alertEl.innerHTML = `
+            <div class="alert-indicator" title="${escapeHtml(detail)}">
+                <span>⚠</span>
+                <span>${data.alerts.length} 条告警</span>
+            </div>
+        `;
+    } else {
+        alertEl.innerHTML = '';
+    }
+    
     // 电池
     const batEl = document.getElementById('batteryIndicator');
     if (data.battery) {
         const b = data.battery;
-        const icon = b.is_charging ? '⚡' : '🔋';
+        const icon = b.is_charging ? '⚡' : b.ac_online ? '🔌' : '🔋';
         const cls = b.is_charging ? 'charging' : 'not-charging';
-        const time = b.time_remaining_minutes 
-            ? `· ${Math.floor(b.time_remaining_minutes / 60)}h ${b.time_remaining_minutes % 60}m`
+        // 优先使用按最近耗电速度估算的时间，刚拔电源时退回驱动的瞬时值
+        const minutes = b.is_charging
+            ? b.estimated_full_minutes
+            : (b.estimated_runtime_minutes ?? b.time_remaining_minutes);
+        const time = minutes 
+            ? `· ${Math.floor(minutes / 60)}h ${minutes % 60}m${b.is_charging ? ' 充满' : ''}`
             : '';
+        const details = (b.batteries || []).map(d =>
+            `${[d.vendor, d.model].filter(Boolean).join(' ') || '电池'}: ${d.percentage.toFixed(0)}% ${d.state}, ` +
+            `${d.energy_rate_watts.toFixed(1)}W, ${d.voltage.toFixed(2)}V, 健康 ${d.health_percent.toFixed(0)}%` +
+            (d.cycle_count != null ? `, 循环 ${d.cycle_count} 次` : '')
+        ).join('\n');
         batEl.innerHTML = `
-            <div class="battery-indicator ${cls}">
+            <div class="battery-indicator ${cls}" title="${escapeHtml(details)}">
                 <span>${icon}</span>
                 <span>${b.percentage.toFixed(0)}% ${time}</span>
             </div>
@@ -243,15 +194,22 @@ function updateUI(data) {
     document.getElementById('diskList').innerHTML = data.disks.map(d => `
         <div class="disk-item">
             <div class="disk-header">
-                <span class="disk-name">${d.name}</span>
+                <span class="disk-name">${escapeHtml(d.name)}</span>
                 <span class="disk-percent">${d.usage_percent.toFixed(1)}%</span>
             </div>
             <div class="disk-bar">
                 <div class="disk-fill" style="width: ${d.usage_percent}%"></div>
             </div>
-            <div class="disk-info">${d.used_gb.toFixed(1)} GB / ${d.total_gb.toFixed(1)} GB · ${d.mount_point}</div>
+            <div class="disk-info">${d.used_gb.toFixed(1)} GB / ${d.total_gb.toFixed(1)} GB · ${escapeHtml(d.mount_point)}</div>
+            <div class="disk-info">
+                ${escapeHtml(d.fs_type)}${d.read_only ? ' · 只读' : ''}${d.no_exec ? ' · noexec' : ''}
+                ${d.inodes_usage_percent !== null ? ` · inode ${d.inodes_usage_percent.toFixed(1)}%` : ''}
+                ${d.forecast && d.forecast.hours_to_full !== null ? ` · 预计 ${formatHours(d.forecast.hours_to_full)} 后写满` : ''}
+            </div>
+            ${diskIoLine(data.disk_io, d.device)}
+            ${smartLine(d.smart)}
         </div>
-    `).join('');
+    `).join('') + storageHealthHtml(data.storage_health);
     
     // 网络进阶 + 硬件传感器
     const netSenEl = document.getElementById('networkSensors');
@@ -279,15 +237,17 @@ function updateUI(data) {
         netSenHtml += '<div class="network-interfaces">';
         net.interfaces.forEach(n => {
             netSenHtml += `
-                <div class="network-item">
-                    <span class="network-name">${n.name}</span>
-                    <span class="network-traffic">↓${n.received_mb}MB ↑${n.transmitted_mb}MB</span>
+                <div class="network-item" title="${interfaceDetails(n)}">
+                    <span class="network-name">${escapeHtml(n.name)}${n.operstate && n.operstate !== 'up' ? ` (${escapeHtml(n.operstate)})` : ''}${n.speed_mbps ? ` · ${n.speed_mbps}Mb/s` : ''}${wirelessLabel(n.wireless)}</span>
+                    <span class="network-traffic">↓${formatRate(n.rx_bytes_per_sec)} ↑${formatRate(n.tx_bytes_per_sec)}${n.errors_per_sec + n.drops_per_sec > 0 ? ' ⚠' : ''}</span>
                 </div>
             `;
         });
         netSenHtml += '</div>';
     }
     
+    netSenHtml += probesHtml(data.probes);
+    
     // 硬件传感器
     netSenHtml += '<div class="section-title" style="margin-top: 16px;">硬件传感器</div>';
     netSenHtml += '<div class="sensor-grid">';
@@ -330,16 +290,483 @@ function updateUI(data) {
     
     // 如果没有传感器数据
     if (sen.cpu_temp_celsius === null && sen.motherboard_temp_celsius === null && 
-        sen.cpu_fan_rpm === null && sen.cpu_voltage === null) {
+        sen.cpu_fan_rpm === null && sen.cpu_voltage === null && sen.chips.length === 0 &&
+        sen.thermal_zones.length === 0) {
         netSenHtml += `
             <div class="no-data">未检测到传感器数据</div>
         `;
     }
     
     netSenHtml += '</div>';
+    netSenHtml += sensorChipsHtml(sen.chips);
+    netSenHtml += thermalHtml(sen.thermal_zones, sen.cooling_devices);
+    netSenHtml += powerHtml(data.power);
     netSenEl.innerHTML = netSenHtml;
 }
 
+// RAPL 功耗和每日用电量
+function powerHtml(p) {
+    if (!p || !p.available) return '';
+    const domains = p.domains.filter(d => d.watts != null).map(d => `
+        <div class="sensor-item" title="${escapeHtml(d.id)}">
+            <span class="sensor-label">${escapeHtml(d.parent ? `${d.parent} / ${d.name}` : d.name)}</span>
+            <span class="sensor-value">${d.watts.toFixed(1)}W</span>
+        </div>
+    `);
+    if (p.total_watts != null) {
+        domains.unshift(`
+            <div class="sensor-item">
+                <span class="sensor-label">合计</span>
+                <span class="sensor-value">${p.total_watts.toFixed(1)}W</span>
+            </div>
+        `);
+    }
+    const days = p.daily_energy.slice().reverse().map(d => `
+        <div class="sensor-item">
+            <span class="sensor-label">${d.date}</span>
+            <span class="sensor-value">${d.kwh.toFixed(3)} kWh</span>
+        </div>
+    `);
+    return `
+        <div class="section-title" style="margin-top: 16px;">功耗</div>
+        <div class="sensor-grid">${domains.join('')}${days.join('')}</div>
+    `;
+}
+
+// 温区（附最低的触发点）和正在工作的散热设备
+function thermalHtml(zones, coolers) {
+    const items = (zones || []).filter(z => z.temperature_celsius != null).map(z => {
+        const next = z.trip_points.slice().sort((a, b) => a.temperature_celsius - b.temperature_celsius)[0];
+        const trips = z.trip_points.map(t => `${t.trip_type} ${t.temperature_celsius.toFixed(0)}°C`).join(' / ');
+        const flag = next && z.temperature_celsius >= next.temperature_celsius ? ' ⚠' : '';
+        return `
+            <div class="sensor-item" title="${escapeHtml(trips)}">
+                <span class="sensor-label">${escapeHtml(z.zone_type || z.name)}</span>
+                <span class="sensor-value">${z.temperature_celsius.toFixed(1)}°C${flag}</span>
+            </div>
+        `;
+    });
+    (coolers || []).filter(c => c.max_state > 0).forEach(c => {
+        items.push(`
+            <div class="sensor-item">
+                <span class="sensor-label">${escapeHtml(c.device_type)}</span>
+                <span class="sensor-value">${c.cur_state ?? '-'} / ${c.max_state}</span>
+            </div>
+        `);
+    });
+    if (items.length === 0) return '';
+    return `
+        <div class="sensor-chip-title">温区 / 散热设备</div>
+        <div class="sensor-grid">${items.join('')}</div>
+    `;
+}
+
+const SENSOR_UNITS = { temperature: '°C', fan: ' RPM', voltage: 'V', current: 'A', power: 'W' };
+
+// 单个传感器读数，超过 max / crit 时标记
+function sensorReadingHtml(r) {
+    const digits = r.kind === 'fan' ? 0 : r.kind === 'temperature' ? 1 : 2;
+    const unit = SENSOR_UNITS[r.kind] || '';
+    const limits = [r.max != null ? `max ${r.max.toFixed(digits)}${unit}` : '', r.crit != null ? `crit ${r.crit.toFixed(digits)}${unit}` : '']
+        .filter(Boolean).join(' / ');
+    const flag = r.crit != null && r.value >= r.crit ? ' 🔴' : r.max != null && r.value >= r.max ? ' ⚠' : '';
+    return `
+        <div class="sensor-item" title="${limits}">
+            <span class="sensor-label">${escapeHtml(r.label || r.channel)}</span>
+            <span class="sensor-value">${r.value.toFixed(digits)}${unit}${flag}</span>
+        </div>
+    `;
+}
+
+// 所有 hwmon 芯片，按芯片分组
+function sensorChipsHtml(chips) {
+    if (!chips || chips.length === 0) return '';
+    return chips.map(c => `
+        <div class="sensor-chip-title">${escapeHtml(c.name)} <span class="sensor-chip-id">${escapeHtml(c.hwmon)}</span></div>
+        <div class="sensor-grid">${c.readings.map(sensorReadingHtml).join('')}</div>
+    `).join('');
+}
+
+// 小时数转为可读时长
+function formatHours(hours) {
+    if (hours < 1) return `${Math.round(hours * 60)} 分钟`;
+    if (hours < 48) return `${hours.toFixed(1)} 小时`;
+    return `${(hours / 24).toFixed(0)} 天`;
+}
+
+// RAID / ZFS 状态
+function storageHealthHtml(h) {
+    if (!h) return '';
+    const rows = [];
+    h.md_arrays.forEach(a => {
+        const sync = a.sync ? ` · ${a.sync.action} ${a.sync.progress_percent.toFixed(1)}%` : '';
+        rows.push(`${a.name} ${a.level || ''} ${a.member_status || a.state}${a.degraded ? ' · 降级' : ''}${sync}`);
+    });
+    if (h.zfs) {
+        h.zfs.pools.forEach(p => rows.push(`ZFS ${p.name} · ${p.state}`));
+    }
+    if (rows.length === 0) return '';
+    return `
+        <div class="disk-item">
+            ${rows.map(r => `<div class="disk-info">${escapeHtml(r)}</div>`).join('')}
+        </div>
+    `;
+}
+
+// PCIe 速率（GT/s）对应的代数
+const PCIE_GENERATIONS = { 2.5: 1, 5: 2, 8: 3, 16: 4, 32: 5, 64: 6 };
+
+function pcieLabel(speed, width) {
+    if (speed == null) return '';
+    const gen = PCIE_GENERATIONS[speed];
+    return `${gen ? `Gen${gen}` : `${speed} GT/s`}${width ? ` x${width}` : ''}`;
+}
+
+// GPU 驱动和 PCIe 链路，当前链路低于最大值时一并显示最大值
+function gpuBusLine(g) {
+    const parts = [];
+    if (g.nvml_index !== null && g.nvml_index !== undefined) parts.push(`NVML ${g.nvml_index}`);
+    if (g.drm_card !== null && g.drm_card !== undefined) parts.push(`card${g.drm_card}`);
+    if (g.driver) parts.push(`驱动 ${escapeHtml(g.driver)}`);
+    const link = g.pcie_link;
+    if (link) {
+        const current = pcieLabel(link.current_speed_gts, link.current_width);
+        const max = pcieLabel(link.max_speed_gts, link.max_width);
+        parts.push(current && max && current !== max ? `PCIe ${current}（最大 ${max}）` : `PCIe ${current || max}`);
+    }
+    if (parts.length === 0) return '';
+    return `<div class="resource-meta" title="${escapeHtml(`${g.pci_bus_id || ''} ${g.pci_id || ''}`)}">${parts.join(' · ')}</div>`;
+}
+
+const GPU_THROTTLE_LABELS = {
+    applications_clocks: '应用频率设置',
+    sw_power_cap: '功耗上限',
+    hw_slowdown: '硬件降频',
+    sync_boost: '同步加速组',
+    sw_thermal_slowdown: '温度（软件）',
+    hw_thermal_slowdown: '温度（硬件）',
+    hw_power_brake_slowdown: '电源制动',
+    display_clock: '显示频率',
+};
+
+// 单个 GPU：占用率、显存、温度、频率、进程
+function renderGpu(g) {
+    const vendorIcon = g.vendor === 'NVIDIA' ? '🟢' : 
+                      g.vendor === 'AMD' ? '🔴' : '🔵';
+    
+    let gpuHtml = `
+        <div class="resource-value">${g.usage_percent}%</div>
+        <div class="progress-bar">
+            <div class="progress-fill gpu" style="width: ${g.usage_percent}%"></div>
+        </div>
+        <div class="resource-meta" style="margin-top: 12px;">${vendorIcon} #${g.index} ${escapeHtml(g.vendor)} - ${escapeHtml(g.name)}</div>
+        ${gpuBusLine(g)}
+        <div class="gpu-details">
+    `;
+    
+    // 显存（如果有）
+    if (g.memory_total_mb > 0) {
+        gpuHtml += `
+            <div class="gpu-detail-item">
+                <span class="gpu-detail-label">显存</span>
+                <span class="gpu-detail-value">${(g.memory_used_mb/1024).toFixed(2)} / ${(g.memory_total_mb/1024).toFixed(2)} GB</span>
+            </div>
+        `;
+    }
+    
+    // GTT（AMD 集显使用的系统内存）
+    if (g.gtt_total_mb) {
+        gpuHtml += `
+            <div class="gpu-detail-item">
+                <span class="gpu-detail-label">GTT</span>
+                <span class="gpu-detail-value">${(g.gtt_used_mb/1024).toFixed(2)} / ${(g.gtt_total_mb/1024).toFixed(2)} GB</span>
+            </div>
+        `;
+    }
+    
+    // 温度（如果有）
+    if (g.temperature > 0) {
+        gpuHtml += `
+            <div class="gpu-detail-item">
+                <span class="gpu-detail-label">温度</span>
+                <span class="gpu-detail-value">${g.temperature}°C</span>
+            </div>
+        `;
+    }
+    
+    // 风扇转速（如果有）
+    if (g.fan_speed_percent !== null && g.fan_speed_percent !== undefined) {
+        gpuHtml += `
+            <div class="gpu-detail-item">
+                <span class="gpu-detail-label">风扇</span>
+                <span class="gpu-detail-value">${g.fan_speed_percent}%</span>
+            </div>
+        `;
+    }
+    
+    // 功耗（如果有）
+    if (g.power_watts !== null && g.power_watts !== undefined) {
+        gpuHtml += `
+            <div class="gpu-detail-item">
+                <span class="gpu-detail-label">功耗</span>
+                <span class="gpu-detail-value">${g.power_watts.toFixed(1)} W</span>
+            </div>
+        `;
+    }
+    
+    // 功耗上限、性能状态（NVIDIA）
+    if (g.power_limit_watts != null) {
+        gpuHtml += `
+            <div class="gpu-detail-item">
+                <span class="gpu-detail-label">功耗上限</span>
+                <span class="gpu-detail-value">${g.power_limit_watts.toFixed(0)} W</span>
+            </div>
+        `;
+    }
+    if (g.performance_state) {
+        gpuHtml += `
+            <div class="gpu-detail-item">
+                <span class="gpu-detail-label">性能状态</span>
+                <span class="gpu-detail-value">${g.performance_state}</span>
+            </div>
+        `;
+    }
+    
+    // 编解码器占用
+    if (g.encoder_usage_percent != null || g.decoder_usage_percent != null) {
+        gpuHtml += `
+            <div class="gpu-detail-item">
+                <span class="gpu-detail-label">编码 / 解码</span>
+                <span class="gpu-detail-value">${g.encoder_usage_percent ?? '-'}% / ${g.decoder_usage_percent ?? '-'}%</span>
+            </div>
+        `;
+    }
+    
+    // PCIe 吞吐
+    if (g.pcie_tx_kb_per_sec != null && g.pcie_rx_kb_per_sec != null) {
+        gpuHtml += `
+            <div class="gpu-detail-item">
+                <span class="gpu-detail-label">PCIe 收/发</span>
+                <span class="gpu-detail-value">↓${formatRate(g.pcie_rx_kb_per_sec * 1024)} ↑${formatRate(g.pcie_tx_kb_per_sec * 1024)}</span>
+            </div>
+        `;
+    }
+    
+    // ECC 错误（开启 ECC 时）
+    if (g.ecc_errors) {
+        gpuHtml += `
+            <div class="gpu-detail-item">
+                <span class="gpu-detail-label">ECC 可纠正/不可纠正</span>
+                <span class="gpu-detail-value">${g.ecc_errors.corrected_volatile ?? '-'} / ${g.ecc_errors.uncorrected_volatile ?? '-'}</span>
+            </div>
+        `;
+    }
+    
+    // 降频原因（空闲降频不显示）
+    const throttles = (g.throttle_reasons || []).filter(r => r !== 'gpu_idle');
+    if (throttles.length > 0) {
+        gpuHtml += `
+            <div class="gpu-detail-item">
+                <span class="gpu-detail-label">降频原因</span>
+                <span class="gpu-detail-value">${throttles.map(r => GPU_THROTTLE_LABELS[r] || r).join('、')}</span>
+            </div>
+        `;
+    }
+    
+    // 核心频率（如果有）
+    if (g.core_clock_mhz !== null && g.core_clock_mhz !== undefined) {
+        gpuHtml += `
+            <div class="gpu-detail-item">
+                <span class="gpu-detail-label">核心频率</span>
+                <span class="gpu-detail-value">${g.core_clock_mhz} MHz</span>
+            </div>
+        `;
+    }
+    
+    // 显存频率（如果有）
+    if (g.memory_clock_mhz !== null && g.memory_clock_mhz !== undefined) {
+        gpuHtml += `
+            <div class="gpu-detail-item">
+                <span class="gpu-detail-label">显存频率</span>
+                <span class="gpu-detail-value">${g.memory_clock_mhz} MHz</span>
+            </div>
+        `;
+    }
+    
+    gpuHtml += '</div>';
+    
+    // 占用显存的进程（如果有）
+    if (g.top_processes && g.top_processes.length > 0) {
+        gpuHtml += `
+            <div class="gpu-processes">
+                <div class="gpu-process-title">GPU 进程</div>
+                ${g.top_processes.map(p => `
+                    <div class="gpu-process-item">
+                        <span>${escapeHtml(p.name)} (PID: ${p.pid})</span>
+                        <span>${p.usage_percent != null ? `${p.usage_percent.toFixed(0)}% · ` : ''}${p.memory_mb} MB</span>
+                    </div>
+                `).join('')}
+            </div>
+        `;
+    }
+    
+    return `<div class="gpu-entry">${gpuHtml}</div>`;
+}
+
+// 单个逻辑 CPU：占用率、调频策略、频率和降频标记
+function coreItemHtml(ca, i) {
+    const usage = ca.per_core_usage[i];
+    const f = ca.per_core_frequency[i];
+    const throttled = f && f.throttled;
+    return `
+        <div class="cpu-core-item ${throttled ? 'throttled' : ''}">
+            <div class="core-label">核心 ${i + 1}${f && f.governor ? ' · ' + escapeHtml(f.governor) : ''}</div>
+            <div class="core-bar">
+                <div class="core-fill" style="width: ${usage}%"></div>
+            </div>
+            <div class="core-value">${usage.toFixed(0)}%</div>
+            ${f ? `<div class="core-freq">${f.current_mhz} MHz${throttled ? ' ⚠降频' : ''}</div>` : ''}
+        </div>
+    `;
+}
+
+// 各核心占用率：多插槽或多 NUMA 节点时按（插槽, 节点）分组，组标题显示节点的 CPU / 内存占用
+function cpuCoresHtml(ca) {
+    const t = ca.topology;
+    const indices = ca.per_core_usage.map((_, i) => i);
+    if (!t || (t.sockets <= 1 && t.numa_nodes.length <= 1)) {
+        return indices.map(i => coreItemHtml(ca, i)).join('');
+    }
+
+    // sysinfo 的核心顺序与 cpu0、cpu1 ... 一致
+    const byCpu = new Map(t.cpus.map(c => [c.cpu, c]));
+    const groups = new Map();
+    indices.forEach(i => {
+        const c = byCpu.get(i) || {};
+        const key = `${c.package_id ?? '-'}/${c.numa_node ?? '-'}`;
+        if (!groups.has(key)) groups.set(key, { package_id: c.package_id, numa_node: c.numa_node, cores: [] });
+        groups.get(key).cores.push(i);
+    });
+
+    return [...groups.values()].map(g => {
+        const parts = [];
+        if (g.package_id != null) parts.push(`插槽 ${g.package_id}`);
+        const node = t.numa_nodes.find(n => n.node === g.numa_node);
+        if (node) {
+            parts.push(`节点 ${node.node}`);
+            parts.push(`CPU ${node.cpu_usage.toFixed(0)}%`);
+            if (node.memory_total_mb > 0) {
+                parts.push(`内存 ${(node.memory_used_mb / 1024).toFixed(1)}GB / ${(node.memory_total_mb / 1024).toFixed(1)}GB`);
+            }
+        }
+        return `<div class="cpu-core-group-title">${parts.join(' · ') || '其他'}</div>` +
+            g.cores.map(i => coreItemHtml(ca, i)).join('');
+    }).join('');
+}
+
+// 各级缓存：L1d 48K×8 / L2 1.3M×8 / L3 30M
+function cacheRow(t) {
+    if (!t || t.caches.length === 0) return '';
+    const size = kb => kb >= 1024 ? `${+(kb / 1024).toFixed(1)}M` : `${kb}K`;
+    const suffix = { Data: 'd', Instruction: 'i' };
+    const caches = t.caches.map(c =>
+        `L${c.level}${suffix[c.cache_type] || ''} ${size(c.size_kb)}${c.instances > 1 ? `×${c.instances}` : ''}`
+    );
+    return `
+        <div class="cpu-info-row">
+            <span class="cpu-info-label">缓存</span>
+            <span class="cpu-info-value">${escapeHtml(caches.join(' / '))}</span>
+        </div>
+    `;
+}
+
+// 网速：字节/秒 -> 可读单位
+function formatRate(bytes) {
+    if (bytes >= 1024 * 1024) return `${(bytes / 1024 / 1024).toFixed(1)}MB/s`;
+    if (bytes >= 1024) return `${(bytes / 1024).toFixed(1)}KB/s`;
+    return `${bytes.toFixed(0)}B/s`;
+}
+
+// 主动探测：状态、平均延迟、失败率
+function probesHtml(probes) {
+    if (!probes || probes.length === 0) return '';
+    return '<div class="network-interfaces">' + probes.map(p => {
+        const last = p.last;
+        const state = !last ? '--' : last.success ? '✓' : '✗';
+        const latency = p.avg_latency_ms !== null ? `${p.avg_latency_ms.toFixed(1)}ms` : '--';
+        return `
+            <div class="network-item" title="${escapeHtml(`${p.kind}://${p.target}`)}${last && last.error ? '&#10;' + escapeHtml(last.error) : ''}">
+                <span class="network-name">${state} ${escapeHtml(p.name)}</span>
+                <span class="network-traffic">${latency} · 失败 ${p.loss_percent.toFixed(0)}%</span>
+            </div>
+        `;
+    }).join('') + '</div>';
+}
+
+// 无线网卡：SSID、信号强度、速率
+function wirelessLabel(w) {
+    if (!w) return '';
+    const parts = [];
+    if (w.ssid) parts.push(escapeHtml(w.ssid));
+    if (w.signal_dbm !== null) parts.push(`${w.signal_dbm} dBm`);
+    if (w.tx_bitrate_mbps !== null) parts.push(`${w.tx_bitrate_mbps}Mb/s`);
+    return parts.length ? ` · 📶 ${parts.join(' · ')}` : '';
+}
+
+// 网络接口详情（鼠标悬停显示）：MAC、MTU、地址
+function interfaceDetails(n) {
+    const lines = [];
+    if (n.mac_address) lines.push(`MAC ${n.mac_address}`);
+    if (n.mtu) lines.push(`MTU ${n.mtu}${n.duplex ? ` · ${n.duplex} duplex` : ''}`);
+    n.addresses.forEach(a => lines.push(`${a.address}/${a.prefix_len}`));
+    if (n.carrier_changes) lines.push(`链路变化 ${n.carrier_changes} 次`);
+    return lines.map(escapeHtml).join('&#10;');
+}
+
+// SMART：健康、温度、通电时间、坏扇区 / NVMe 寿命
+function smartLine(s) {
+    if (!s) return '';
+    const parts = [`SMART ${s.health_passed === false ? '未通过' : s.health_passed ? '正常' : '--'}`];
+    if (s.temperature_celsius !== null) parts.push(`${s.temperature_celsius}°C`);
+    if (s.power_on_hours !== null) parts.push(`通电 ${s.power_on_hours} 小时`);
+    if (s.reallocated_sectors) parts.push(`重映射扇区 ${s.reallocated_sectors}`);
+    if (s.nvme_percentage_used !== null) parts.push(`寿命消耗 ${s.nvme_percentage_used}%`);
+    return `<div class="disk-info">${parts.join(' · ')}</div>`;
+}
+
+// 磁盘 IO：读写速率、IOPS、利用率
+function diskIoLine(diskIo, device) {
+    const io = (diskIo || []).find(x => x.name === device);
+    if (!io) return '';
+    const mb = x => (x / 1024 / 1024).toFixed(1);
+    return `
+        <div class="disk-info">
+            R ${mb(io.read_bytes_per_sec)} MB/s · W ${mb(io.write_bytes_per_sec)} MB/s ·
+            ${(io.read_iops + io.write_iops).toFixed(0)} IOPS · ${io.utilization_percent.toFixed(0)}%
+        </div>
+    `;
+}
+
+// 资源压力（PSI）：CPU / 内存 / IO 的 some avg10，以及最近 1 小时内存 / IO full avg10 的峰值
+function psiRow(p) {
+    if (!p || !p.available) return '';
+    const fmt = x => x ? x.some.avg10.toFixed(1) + '%' : '--';
+    const peak = key => {
+        const values = (p.history || []).map(s => s[key]).filter(v => v != null);
+        return values.length ? Math.max(...values).toFixed(1) + '%' : '--';
+    };
+    return `
+        <div class="cpu-info-row">
+            <span class="cpu-info-label">压力 CPU/MEM/IO</span>
+            <span class="cpu-info-value">${fmt(p.cpu)} / ${fmt(p.memory)} / ${fmt(p.io)}</span>
+        </div>
+        <div class="cpu-info-row">
+            <span class="cpu-info-label">1h 峰值 full MEM/IO</span>
+            <span class="cpu-info-value">${peak('memory_full')} / ${peak('io_full')}</span>
+        </div>
+    `;
+}
+
 // 页面可见性控制
 document.addEventListener('visibilitychange', () => {
     if (document.hidden) {
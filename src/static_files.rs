@@ -15,7 +15,7 @@ pub async fn serve_static(uri: Uri) -> impl IntoResponse {
     
     match Assets::get(path) {
         Some(content) => {
-            let mime = match path.split('.').last() {
+            let mime = match path.split('.').next_back() {
                 Some("css") => "text/css",
                 Some("js") => "application/javascript",
                 Some("html") => "text/html",
@@ -0,0 +1,517 @@
+use crate::collector::DiskInfo;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+pub const DISKSTATS_PATH: &str = "/proc/diskstats";
+
+// /proc/diskstats 中的扇区固定按 512 字节计，与设备实际扇区大小无关
+const SECTOR_SIZE: u64 = 512;
+
+/// 单个块设备的累计计数器（/proc/diskstats 的一行）
+#[derive(Clone)]
+pub struct DiskCounters {
+    pub reads: u64,
+    pub sectors_read: u64,
+    pub read_ms: u64,
+    pub writes: u64,
+    pub sectors_written: u64,
+    pub write_ms: u64,
+    pub in_flight: u64,
+    pub io_ms: u64,
+    pub weighted_io_ms: u64,
+}
+
+/// 设备名 -> 计数器
+pub type DiskStatsSnapshot = HashMap<String, DiskCounters>;
+
+#[derive(Serialize, Clone)]
+pub struct BlockDeviceIo {
+    // 设备名，如 sda / nvme0n1p2 / dm-0
+    pub name: String,
+    // 该设备上的挂载点（对应 disks 列表）
+    pub mount_points: Vec<String>,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    // 平均每个请求的耗时（含排队），对应 iostat 的 r_await / w_await
+    pub avg_read_latency_ms: f64,
+    pub avg_write_latency_ms: f64,
+    // 平均队列深度，对应 iostat 的 aqu-sz
+    pub queue_depth: f64,
+    // 设备忙碌时间占比，对应 iostat 的 %util
+    pub utilization_percent: f64,
+    // 当前正在处理的请求数
+    pub in_flight: u64,
+}
+
+/// 读取 /proc/diskstats
+pub fn read_diskstats(path: &Path) -> Option<DiskStatsSnapshot> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|content| parse_diskstats(&content))
+}
+
+/// 解析 /proc/diskstats，格式：
+/// `major minor name reads merged sectors ms writes merged sectors ms in_flight io_ms weighted_ms ...`
+pub fn parse_diskstats(content: &str) -> DiskStatsSnapshot {
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 14 {
+                return None;
+            }
+            let value = |i: usize| fields[i].parse::<u64>().unwrap_or(0);
+            Some((
+                fields[2].to_string(),
+                DiskCounters {
+                    reads: value(3),
+                    sectors_read: value(5),
+                    read_ms: value(6),
+                    writes: value(7),
+                    sectors_written: value(9),
+                    write_ms: value(10),
+                    in_flight: value(11),
+                    io_ms: value(12),
+                    weighted_io_ms: value(13),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// 根据两次快照计算每个块设备的吞吐、IOPS、延迟和利用率
+/// 没有上次快照时速率均为 0；loop / ram 设备和从未有过 IO 的设备不显示
+pub fn device_io(
+    previous: Option<&DiskStatsSnapshot>,
+    current: &DiskStatsSnapshot,
+    elapsed_secs: f64,
+    mounts: &HashMap<String, Vec<String>>,
+) -> Vec<BlockDeviceIo> {
+    let mut devices: Vec<BlockDeviceIo> = current
+        .iter()
+        .filter(|(name, counters)| {
+            !name.starts_with("loop")
+                && !name.starts_with("ram")
+                && counters.reads + counters.writes > 0
+        })
+        .map(|(name, now)| {
+            let before = previous.and_then(|p| p.get(name));
+            let delta = |f: fn(&DiskCounters) -> u64| match before {
+                Some(before) => f(now).saturating_sub(f(before)),
+                None => 0,
+            };
+            let per_sec = |value: u64| {
+                if elapsed_secs > 0.0 {
+                    value as f64 / elapsed_secs
+                } else {
+                    0.0
+                }
+            };
+            let per_request = |ms: u64, requests: u64| {
+                if requests > 0 {
+                    ms as f64 / requests as f64
+                } else {
+                    0.0
+                }
+            };
+
+            let reads = delta(|c| c.reads);
+            let writes = delta(|c| c.writes);
+            let elapsed_ms = elapsed_secs * 1000.0;
+
+            BlockDeviceIo {
+                name: name.clone(),
+                mount_points: mounts.get(name).cloned().unwrap_or_default(),
+                read_bytes_per_sec: per_sec(delta(|c| c.sectors_read) * SECTOR_SIZE),
+                write_bytes_per_sec: per_sec(delta(|c| c.sectors_written) * SECTOR_SIZE),
+                read_iops: per_sec(reads),
+                write_iops: per_sec(writes),
+                avg_read_latency_ms: per_request(delta(|c| c.read_ms), reads),
+                avg_write_latency_ms: per_request(delta(|c| c.write_ms), writes),
+                queue_depth: if elapsed_ms > 0.0 {
+                    delta(|c| c.weighted_io_ms) as f64 / elapsed_ms
+                } else {
+                    0.0
+                },
+                utilization_percent: if elapsed_ms > 0.0 {
+                    (delta(|c| c.io_ms) as f64 / elapsed_ms * 100.0).min(100.0)
+                } else {
+                    0.0
+                },
+                in_flight: now.in_flight,
+            }
+        })
+        .collect();
+
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+/// 把挂载源（/dev/sda1、/dev/mapper/vg-root 等）解析为内核块设备名
+pub fn resolve_block_device(source: &str) -> Option<String> {
+    if !source.starts_with("/dev/") {
+        return None;
+    }
+    // /dev/mapper/* 和 /dev/disk/by-* 都是指向 /dev/dm-N、/dev/sdX 的符号链接
+    let path = fs::canonicalize(source).ok()?;
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+pub const MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+
+// 默认排除的伪文件系统 / 容器层 / 只读镜像
+const DEFAULT_EXCLUDE_FS_TYPES: &[&str] = &[
+    "tmpfs",
+    "devtmpfs",
+    "overlay",
+    "squashfs",
+    "ramfs",
+    "efivarfs",
+    "nsfs",
+    "fuse.snapfuse",
+];
+// 默认排除的挂载路径前缀
+const DEFAULT_EXCLUDE_MOUNTS: &[&str] = &["/snap", "/var/lib/docker", "/run"];
+
+/// 磁盘列表过滤规则
+/// 环境变量（逗号分隔）：
+/// - MONITOR_FS_INCLUDE_TYPES：只保留这些文件系统类型（设置后忽略排除列表）
+/// - MONITOR_FS_EXCLUDE_TYPES：排除的文件系统类型
+/// - MONITOR_MOUNT_INCLUDE：只保留这些路径下的挂载点
+/// - MONITOR_MOUNT_EXCLUDE：排除这些路径下的挂载点
+#[derive(Clone)]
+pub struct DiskFilter {
+    pub include_fs_types: Vec<String>,
+    pub exclude_fs_types: Vec<String>,
+    pub include_mounts: Vec<String>,
+    pub exclude_mounts: Vec<String>,
+}
+
+impl DiskFilter {
+    pub fn from_env() -> Self {
+        let list = |name: &str, default: &[&str]| match std::env::var(name) {
+            Ok(value) => value
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            Err(_) => default.iter().map(|s| s.to_string()).collect(),
+        };
+
+        Self {
+            include_fs_types: list("MONITOR_FS_INCLUDE_TYPES", &[]),
+            exclude_fs_types: list("MONITOR_FS_EXCLUDE_TYPES", DEFAULT_EXCLUDE_FS_TYPES),
+            include_mounts: list("MONITOR_MOUNT_INCLUDE", &[]),
+            exclude_mounts: list("MONITOR_MOUNT_EXCLUDE", DEFAULT_EXCLUDE_MOUNTS),
+        }
+    }
+
+    /// 判断某个挂载点是否需要显示
+    pub fn matches(&self, fs_type: &str, mount_point: &str) -> bool {
+        let type_ok = if self.include_fs_types.is_empty() {
+            !self.exclude_fs_types.iter().any(|t| t == fs_type)
+        } else {
+            self.include_fs_types.iter().any(|t| t == fs_type)
+        };
+
+        let mount_ok = (self.include_mounts.is_empty()
+            || self.include_mounts.iter().any(|p| is_under(mount_point, p)))
+            && !self.exclude_mounts.iter().any(|p| is_under(mount_point, p));
+
+        type_ok && mount_ok
+    }
+}
+
+/// 路径前缀匹配（按目录边界，/run 不匹配 /runner）
+fn is_under(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    if prefix.is_empty() {
+        return true;
+    }
+    path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// /proc/self/mountinfo 中的一条挂载记录
+pub struct MountEntry {
+    pub fs_type: String,
+    // 挂载选项（每个挂载点自己的选项，如 ro / noexec / nosuid）
+    pub options: Vec<String>,
+}
+
+/// 读取挂载信息，挂载点 -> 记录（同一路径多次挂载时以最后一次为准）
+pub fn read_mountinfo(path: &Path) -> HashMap<String, MountEntry> {
+    fs::read_to_string(path)
+        .map(|content| parse_mountinfo(&content))
+        .unwrap_or_default()
+}
+
+/// 解析 mountinfo，格式：
+/// `36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue`
+pub fn parse_mountinfo(content: &str) -> HashMap<String, MountEntry> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (left, right) = line.split_once(" - ")?;
+            let left: Vec<&str> = left.split_whitespace().collect();
+            let fs_type = right.split_whitespace().next()?;
+            let mount_point = unescape_mount_path(left.get(4)?);
+            let options = left.get(5)?.split(',').map(|s| s.to_string()).collect();
+            Some((
+                mount_point,
+                MountEntry {
+                    fs_type: fs_type.to_string(),
+                    options,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// mountinfo 中空格等字符以八进制转义，如 `\040`
+fn unescape_mount_path(path: &str) -> String {
+    let mut out = Vec::with_capacity(path.len());
+    let bytes = path.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 4 <= bytes.len() {
+            let digits = std::str::from_utf8(&bytes[i + 1..i + 4]).ok();
+            if let Some(value) = digits.and_then(|d| u8::from_str_radix(d, 8).ok()) {
+                out.push(value);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// 跟踪各挂载点的只读状态
+/// /boot/efi、只读 bind mount 等本来就以只读挂载，只有运行期间由读写变为只读
+/// （ext4 等出错后 remount 为只读）才需要告警；重新挂载为读写后清除
+pub struct ReadOnlyTracker {
+    last: HashMap<String, bool>,
+    remounted: HashSet<String>,
+}
+
+impl ReadOnlyTracker {
+    pub fn new() -> Self {
+        Self {
+            last: HashMap::new(),
+            remounted: HashSet::new(),
+        }
+    }
+
+    /// 对比上次采样，设置各挂载点的 remounted_read_only
+    pub fn update(&mut self, disks: &mut [DiskInfo]) {
+        for disk in disks.iter_mut() {
+            let was_writable = self.last.get(&disk.mount_point) == Some(&false);
+            if !disk.read_only {
+                self.remounted.remove(&disk.mount_point);
+            } else if was_writable {
+                self.remounted.insert(disk.mount_point.clone());
+            }
+            disk.remounted_read_only = self.remounted.contains(&disk.mount_point);
+        }
+
+        self.last = disks
+            .iter()
+            .map(|d| (d.mount_point.clone(), d.read_only))
+            .collect();
+        self.remounted.retain(|mount| self.last.contains_key(mount));
+    }
+}
+
+/// 文件系统的 inode 使用情况
+pub struct InodeUsage {
+    pub total: u64,
+    pub used: u64,
+}
+
+/// 通过 statvfs 读取 inode 数量；btrfs 等不限制 inode 的文件系统总数为 0，返回 None
+#[cfg(unix)]
+pub fn inode_usage(mount_point: &Path) -> Option<InodeUsage> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(mount_point.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: path 是以 NUL 结尾的合法字符串，stat 指向有效的可写内存
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+
+    let total = stat.f_files as u64;
+    if total == 0 {
+        return None;
+    }
+    Some(InodeUsage {
+        total,
+        used: total.saturating_sub(stat.f_ffree as u64),
+    })
+}
+
+#[cfg(not(unix))]
+pub fn inode_usage(_mount_point: &Path) -> Option<InodeUsage> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disk(mount_point: &str, read_only: bool) -> DiskInfo {
+        DiskInfo::fixture(mount_point, 10.0, read_only)
+    }
+
+    #[test]
+    fn parses_mountinfo_options_and_escapes() {
+        let mounts = parse_mountinfo(
+            "36 35 98:0 / / rw,relatime shared:1 - ext4 /dev/sda1 rw,errors=remount-ro\n\
+             40 36 8:17 / /mnt/my\\040disk ro,nosuid master:2 - vfat /dev/sdb1 rw\n",
+        );
+        assert_eq!(mounts["/"].fs_type, "ext4");
+        assert_eq!(mounts["/"].options, ["rw", "relatime"]);
+        assert_eq!(mounts["/mnt/my disk"].options, ["ro", "nosuid"]);
+    }
+
+    #[test]
+    fn read_only_from_start_is_not_remounted() {
+        let mut tracker = ReadOnlyTracker::new();
+        let mut disks = vec![disk("/boot/efi", true)];
+        tracker.update(&mut disks);
+        tracker.update(&mut disks);
+        assert!(!disks[0].remounted_read_only);
+    }
+
+    #[test]
+    fn remount_to_read_only_is_sticky_until_writable() {
+        let mut tracker = ReadOnlyTracker::new();
+        tracker.update(&mut [disk("/", false)]);
+
+        let mut disks = [disk("/", true)];
+        tracker.update(&mut disks);
+        assert!(disks[0].remounted_read_only);
+        tracker.update(&mut disks);
+        assert!(disks[0].remounted_read_only);
+
+        let mut disks = [disk("/", false)];
+        tracker.update(&mut disks);
+        assert!(!disks[0].remounted_read_only);
+    }
+
+    #[test]
+    fn filter_matches_directory_boundaries() {
+        let filter = DiskFilter {
+            include_fs_types: Vec::new(),
+            exclude_fs_types: vec!["tmpfs".to_string()],
+            include_mounts: Vec::new(),
+            exclude_mounts: vec!["/run".to_string()],
+        };
+        assert!(filter.matches("ext4", "/runner"));
+        assert!(!filter.matches("ext4", "/run/user/1000"));
+        assert!(!filter.matches("tmpfs", "/dev/shm"));
+    }
+
+    // 4.18 之前 14 个字段；4.18 起加了 discard 的 4 个字段（18 个）；5.5 起加了 flush 的 2 个字段（20 个）
+    const DISKSTATS: &str = "   8       0 sda 1000 50 80000 2000 500 20 40000 3000 0 4000 5000
+   8       1 sda1 900 40 72000 1800 450 15 36000 2700 0 3600 4500 0 0 0 0
+ 259       0 nvme0n1 2000 0 160000 400 1000 0 80000 600 2 900 1000 10 0 2048 5 30 7
+   7       0 loop0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+   1       0 ram0 10 0 20 0 0 0 0 0 0 0
+";
+
+    #[test]
+    fn parses_diskstats_formats() {
+        let stats = parse_diskstats(DISKSTATS);
+        // 字段不足 14 个的行跳过
+        assert_eq!(stats.len(), 4);
+
+        let sda = &stats["sda"];
+        assert_eq!(sda.reads, 1000);
+        assert_eq!(sda.sectors_read, 80000);
+        assert_eq!(sda.read_ms, 2000);
+        assert_eq!(sda.writes, 500);
+        assert_eq!(sda.sectors_written, 40000);
+        assert_eq!(sda.write_ms, 3000);
+        assert_eq!(sda.io_ms, 4000);
+        assert_eq!(sda.weighted_io_ms, 5000);
+
+        // 分区和整盘各自一行，计数互相独立
+        assert_eq!(stats["sda1"].reads, 900);
+        // 20 字段格式：额外的 discard / flush 字段不影响前面的计数
+        let nvme = &stats["nvme0n1"];
+        assert_eq!(nvme.sectors_written, 80000);
+        assert_eq!(nvme.in_flight, 2);
+        assert_eq!(nvme.weighted_io_ms, 1000);
+        assert!(stats.contains_key("loop0"));
+    }
+
+    fn counters(reads: u64, sectors_read: u64, io_ms: u64) -> DiskCounters {
+        DiskCounters {
+            reads,
+            sectors_read,
+            read_ms: reads * 2,
+            writes: 0,
+            sectors_written: 0,
+            write_ms: 0,
+            in_flight: 0,
+            io_ms,
+            weighted_io_ms: io_ms,
+        }
+    }
+
+    #[test]
+    fn computes_device_rates() {
+        let previous = DiskStatsSnapshot::from([
+            ("sda".to_string(), counters(100, 1000, 1000)),
+            ("sda1".to_string(), counters(90, 900, 900)),
+        ]);
+        let current = DiskStatsSnapshot::from([
+            ("sda".to_string(), counters(300, 5000, 2000)),
+            ("sda1".to_string(), counters(290, 4900, 1900)),
+            ("loop0".to_string(), counters(10, 10, 0)),
+            ("sdb".to_string(), counters(0, 0, 0)),
+        ]);
+        let mounts = HashMap::from([("sda1".to_string(), vec!["/".to_string()])]);
+
+        let devices = device_io(Some(&previous), &current, 2.0, &mounts);
+        let names: Vec<_> = devices.iter().map(|d| &*d.name).collect();
+        // loop 设备和没有 IO 的设备不显示
+        assert_eq!(names, vec!["sda", "sda1"]);
+
+        let sda = &devices[0];
+        // 4000 扇区 × 512 字节 / 2 秒
+        assert_eq!(sda.read_bytes_per_sec, 4000.0 * 512.0 / 2.0);
+        assert_eq!(sda.read_iops, 100.0);
+        assert_eq!(sda.avg_read_latency_ms, 2.0);
+        assert_eq!(sda.utilization_percent, 50.0);
+        assert_eq!(sda.queue_depth, 0.5);
+        assert!(sda.mount_points.is_empty());
+        assert_eq!(devices[1].mount_points, vec!["/".to_string()]);
+    }
+
+    #[test]
+    fn device_rates_without_previous_or_after_reset() {
+        let current = DiskStatsSnapshot::from([("sda".to_string(), counters(100, 1000, 1000))]);
+        let devices = device_io(None, &current, 2.0, &HashMap::new());
+        assert_eq!(devices[0].read_bytes_per_sec, 0.0);
+
+        // 计数器变小（设备被移除后重新接入）时按 0 处理
+        let previous = DiskStatsSnapshot::from([("sda".to_string(), counters(500, 9000, 8000))]);
+        let devices = device_io(Some(&previous), &current, 2.0, &HashMap::new());
+        assert_eq!(devices[0].read_bytes_per_sec, 0.0);
+        assert_eq!(devices[0].read_iops, 0.0);
+        assert_eq!(devices[0].utilization_percent, 0.0);
+        assert_eq!(devices[0].avg_read_latency_ms, 0.0);
+    }
+}
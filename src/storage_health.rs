@@ -0,0 +1,431 @@
+use crate::sysfs::{read_string, read_u64};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+pub const MDSTAT_PATH: &str = "/proc/mdstat";
+pub const BLOCK_SYSFS_ROOT: &str = "/sys/block";
+pub const ZFS_KSTAT_ROOT: &str = "/proc/spl/kstat/zfs";
+
+#[derive(Serialize, Clone)]
+pub struct StorageHealth {
+    // 软 RAID（mdraid）阵列
+    pub md_arrays: Vec<MdArray>,
+    // device-mapper 设备（LVM 逻辑卷、LUKS 加密卷等）
+    pub device_mapper: Vec<DmDevice>,
+    // 未加载 zfs 模块时为 None
+    pub zfs: Option<ZfsInfo>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct MdArray {
+    pub name: String,
+    // active / inactive
+    pub state: String,
+    pub read_only: bool,
+    // raid0 / raid1 / raid5 ...
+    pub level: Option<String>,
+    pub members: Vec<MdMember>,
+    // [n/m]：应有成员数 / 正常成员数
+    pub total_devices: Option<u32>,
+    pub active_devices: Option<u32>,
+    // 成员状态，如 [UU_]，U 为正常，_ 为缺失
+    pub member_status: Option<String>,
+    pub degraded: bool,
+    // 正在进行的重建 / 同步 / 校验
+    pub sync: Option<MdSync>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct MdMember {
+    pub device: String,
+    pub faulty: bool,
+    pub spare: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct MdSync {
+    // recovery / resync / reshape / check / repair
+    pub action: String,
+    pub progress_percent: f64,
+    pub finish_minutes: Option<f64>,
+    pub speed_kb_per_sec: Option<u64>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DmDevice {
+    // 内核设备名，如 dm-0
+    pub device: String,
+    // 映射名，如 vg0-root
+    pub name: String,
+    // lvm / crypt / multipath / other（由 uuid 前缀判断）
+    pub kind: String,
+    pub suspended: bool,
+    pub size_gb: f64,
+    // 底层设备
+    pub slaves: Vec<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ZfsInfo {
+    pub arc: Option<ArcStats>,
+    pub pools: Vec<ZfsPool>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ArcStats {
+    pub size_mb: u64,
+    // ARC 当前目标大小和上限
+    pub target_mb: u64,
+    pub max_mb: u64,
+    pub hit_ratio_percent: f64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ZfsPool {
+    pub name: String,
+    // ONLINE / DEGRADED / FAULTED / OFFLINE / UNAVAIL / REMOVED
+    pub state: String,
+}
+
+/// 采集 mdraid、device-mapper 和 ZFS 的健康状态
+pub fn collect_storage_health(mdstat: &Path, block_root: &Path, zfs_root: &Path) -> StorageHealth {
+    StorageHealth {
+        md_arrays: fs::read_to_string(mdstat)
+            .map(|content| parse_mdstat(&content))
+            .unwrap_or_default(),
+        device_mapper: collect_device_mapper(block_root),
+        zfs: collect_zfs(zfs_root),
+    }
+}
+
+/// 解析 /proc/mdstat，格式：
+/// ```text
+/// md1 : active raid5 sdd1[3](F) sdc1[1] sdb2[0]
+///       2095104 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [UU_]
+///       [=>...................]  recovery =  8.7% (91712/1047552) finish=0.6min speed=22928K/sec
+/// ```
+pub fn parse_mdstat(content: &str) -> Vec<MdArray> {
+    let mut arrays: Vec<MdArray> = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        // 阵列首行：`mdX : state [level] members...`
+        if let Some((name, rest)) = trimmed.split_once(" : ")
+            && name.starts_with("md")
+        {
+            let mut words = rest.split_whitespace().peekable();
+            let state = words.next().unwrap_or_default().to_string();
+            let mut read_only = false;
+            while let Some(word) = words.peek() {
+                if word.starts_with('(') {
+                    read_only |= word.contains("read-only");
+                    words.next();
+                } else {
+                    break;
+                }
+            }
+            let level = words
+                .peek()
+                .filter(|w| !w.contains('['))
+                .map(|w| w.to_string());
+            if level.is_some() {
+                words.next();
+            }
+            let members = words.filter_map(parse_md_member).collect();
+
+            arrays.push(MdArray {
+                name: name.to_string(),
+                state,
+                read_only,
+                level,
+                members,
+                total_devices: None,
+                active_devices: None,
+                member_status: None,
+                degraded: false,
+                sync: None,
+            });
+            continue;
+        }
+
+        let Some(array) = arrays.last_mut() else {
+            continue;
+        };
+
+        // 容量行末尾的 [n/m] [UU_]
+        if trimmed.contains(" blocks") {
+            for word in trimmed.split_whitespace() {
+                let Some(inner) = word.strip_prefix('[').and_then(|w| w.strip_suffix(']')) else {
+                    continue;
+                };
+                if let Some((total, active)) = inner.split_once('/') {
+                    array.total_devices = total.parse().ok();
+                    array.active_devices = active.parse().ok();
+                } else if inner.chars().all(|c| c == 'U' || c == '_') {
+                    array.member_status = Some(inner.to_string());
+                }
+            }
+            array.degraded = matches!(
+                (array.total_devices, array.active_devices),
+                (Some(total), Some(active)) if active < total
+            ) || array.members.iter().any(|m| m.faulty);
+            continue;
+        }
+
+        // 进度行：`[=>....]  recovery =  8.7% (...) finish=0.6min speed=22928K/sec`
+        if let Some(sync) = parse_md_sync(trimmed) {
+            array.sync = Some(sync);
+        }
+    }
+
+    arrays
+}
+
+/// 解析成员，如 `sdd1[3](F)`、`sde1[4](S)`
+fn parse_md_member(word: &str) -> Option<MdMember> {
+    let (device, flags) = word.split_once('[')?;
+    Some(MdMember {
+        device: device.to_string(),
+        faulty: flags.contains("(F)"),
+        spare: flags.contains("(S)"),
+    })
+}
+
+fn parse_md_sync(line: &str) -> Option<MdSync> {
+    let (before, after) = line.split_once(" = ")?;
+    let action = before.split_whitespace().last()?.to_string();
+    if !matches!(
+        action.as_str(),
+        "recovery" | "resync" | "check" | "reshape" | "repair"
+    ) {
+        return None;
+    }
+    let progress_percent = after
+        .split_whitespace()
+        .next()?
+        .trim_end_matches('%')
+        .parse()
+        .ok()?;
+
+    let field = |key: &str| {
+        after
+            .split_whitespace()
+            .find_map(|w| w.strip_prefix(key))
+            .map(|v| v.to_string())
+    };
+
+    Some(MdSync {
+        action,
+        progress_percent,
+        finish_minutes: field("finish=").and_then(|v| v.trim_end_matches("min").parse().ok()),
+        speed_kb_per_sec: field("speed=").and_then(|v| v.trim_end_matches("K/sec").parse().ok()),
+    })
+}
+
+fn collect_device_mapper(block_root: &Path) -> Vec<DmDevice> {
+    let mut devices: Vec<DmDevice> = fs::read_dir(block_root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with("dm-"))
+                .filter_map(|entry| {
+                    let path = entry.path();
+                    let dm = path.join("dm");
+                    let name = read_string(dm.join("name"))?;
+                    let uuid = read_string(dm.join("uuid")).unwrap_or_default();
+                    let kind = match uuid.split('-').next() {
+                        Some("LVM") => "lvm",
+                        Some("CRYPT") => "crypt",
+                        Some("mpath") => "multipath",
+                        _ => "other",
+                    };
+                    let slaves = fs::read_dir(path.join("slaves"))
+                        .map(|entries| {
+                            entries
+                                .flatten()
+                                .map(|e| e.file_name().to_string_lossy().to_string())
+                                .collect()
+                        })
+                        .unwrap_or_default();
+
+                    Some(DmDevice {
+                        device: entry.file_name().to_string_lossy().to_string(),
+                        name,
+                        kind: kind.to_string(),
+                        suspended: read_u64(dm.join("suspended")) == Some(1),
+                        // size 以 512 字节扇区计
+                        size_gb: read_u64(path.join("size")).unwrap_or(0) as f64 * 512.0
+                            / 1024.0
+                            / 1024.0
+                            / 1024.0,
+                        slaves,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+fn collect_zfs(zfs_root: &Path) -> Option<ZfsInfo> {
+    if !zfs_root.exists() {
+        return None;
+    }
+
+    let arc = fs::read_to_string(zfs_root.join("arcstats"))
+        .ok()
+        .and_then(|content| parse_arcstats(&content));
+
+    // 每个存储池在 kstat 下有一个目录，OpenZFS 2.x 提供 state 文件
+    let mut pools: Vec<ZfsPool> = fs::read_dir(zfs_root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let state = read_string(entry.path().join("state"))?;
+                    Some(ZfsPool {
+                        name: entry.file_name().to_string_lossy().to_string(),
+                        state,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    pools.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Some(ZfsInfo { arc, pools })
+}
+
+/// 解析 arcstats，格式：前两行为表头，之后每行 `name type data`
+pub fn parse_arcstats(content: &str) -> Option<ArcStats> {
+    let values: std::collections::HashMap<&str, u64> = content
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let value = parts.nth(1)?.parse().ok()?;
+            Some((name, value))
+        })
+        .collect();
+
+    let mb = |key: &str| values.get(key).map(|v| v / 1024 / 1024);
+    let hits = values.get("hits").copied().unwrap_or(0);
+    let misses = values.get("misses").copied().unwrap_or(0);
+
+    Some(ArcStats {
+        size_mb: mb("size")?,
+        target_mb: mb("c").unwrap_or(0),
+        max_mb: mb("c_max").unwrap_or(0),
+        hit_ratio_percent: if hits + misses > 0 {
+            hits as f64 / (hits + misses) as f64 * 100.0
+        } else {
+            0.0
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MDSTAT: &str = "\
+Personalities : [raid1] [raid6] [raid5] [raid4]
+md0 : active raid1 sdb1[1] sda1[0]
+      976630464 blocks super 1.2 [2/2] [UU]
+      bitmap: 2/8 pages [8KB], 65536KB chunk
+
+md1 : active raid1 sdd1[1](F) sdc1[0]
+      488254464 blocks super 1.2 [2/1] [U_]
+
+md2 : active raid5 sdh1[3] sdg1[1] sdf1[0] sde1[4](S)
+      2095104 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [UU_]
+      [=>...................]  recovery =  8.7% (91712/1047552) finish=0.6min speed=22928K/sec
+
+md3 : active (auto-read-only) raid1 sdj1[1] sdi1[0]
+      1048512 blocks [2/2] [UU]
+      [==========>..........]  check = 52.1% (546304/1048512) finish=3.2min speed=2600K/sec
+
+unused devices: <none>
+";
+
+    #[test]
+    fn clean_array() {
+        let arrays = parse_mdstat(MDSTAT);
+        assert_eq!(arrays.len(), 4);
+        let md0 = &arrays[0];
+        assert_eq!(md0.name, "md0");
+        assert_eq!(md0.state, "active");
+        assert_eq!(md0.level.as_deref(), Some("raid1"));
+        assert_eq!((md0.total_devices, md0.active_devices), (Some(2), Some(2)));
+        assert_eq!(md0.member_status.as_deref(), Some("UU"));
+        assert!(!md0.degraded);
+        assert!(md0.sync.is_none());
+        assert_eq!(md0.members.len(), 2);
+    }
+
+    #[test]
+    fn degraded_array_with_faulty_member() {
+        let md1 = &parse_mdstat(MDSTAT)[1];
+        assert!(md1.degraded);
+        assert_eq!(md1.member_status.as_deref(), Some("U_"));
+        let faulty: Vec<&str> = md1
+            .members
+            .iter()
+            .filter(|m| m.faulty)
+            .map(|m| m.device.as_str())
+            .collect();
+        assert_eq!(faulty, ["sdd1"]);
+    }
+
+    #[test]
+    fn array_in_recovery() {
+        let md2 = &parse_mdstat(MDSTAT)[2];
+        assert_eq!(md2.level.as_deref(), Some("raid5"));
+        assert!(md2.degraded);
+        assert!(md2.members.iter().any(|m| m.device == "sde1" && m.spare));
+        let sync = md2.sync.as_ref().unwrap();
+        assert_eq!(sync.action, "recovery");
+        assert_eq!(sync.progress_percent, 8.7);
+        assert_eq!(sync.finish_minutes, Some(0.6));
+        assert_eq!(sync.speed_kb_per_sec, Some(22928));
+    }
+
+    #[test]
+    fn read_only_array_under_check() {
+        let md3 = &parse_mdstat(MDSTAT)[3];
+        assert!(md3.read_only);
+        assert_eq!(md3.level.as_deref(), Some("raid1"));
+        assert!(!md3.degraded);
+        assert_eq!(md3.sync.as_ref().unwrap().action, "check");
+    }
+
+    #[test]
+    fn parses_arcstats() {
+        let arc = parse_arcstats(
+            "\
+13 1 0x01 123 33456 1234567890 9876543210
+name                            type data
+hits                            4    900
+misses                          4    100
+c                               4    2147483648
+c_max                           4    8589934592
+size                            4    1073741824
+",
+        )
+        .unwrap();
+        assert_eq!(arc.size_mb, 1024);
+        assert_eq!(arc.target_mb, 2048);
+        assert_eq!(arc.max_mb, 8192);
+        assert_eq!(arc.hit_ratio_percent, 90.0);
+    }
+
+    #[test]
+    fn arcstats_without_size() {
+        assert!(parse_arcstats("header\nname type data\nhits 4 1\n").is_none());
+    }
+}
@@ -0,0 +1,106 @@
+//! sysfs / procfs 读取辅助函数
+//! 这些文件内容通常是单个值加换行，读取失败（文件不存在、权限不足）一律返回 None
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 读取文件并去掉首尾空白
+pub fn read_string(path: impl AsRef<Path>) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// 读取无符号整数
+pub fn read_u64(path: impl AsRef<Path>) -> Option<u64> {
+    read_string(path).and_then(|s| s.parse().ok())
+}
+
+/// 读取有符号整数（如 hwmon 温度可能为负）
+pub fn read_i64(path: impl AsRef<Path>) -> Option<i64> {
+    read_string(path).and_then(|s| s.parse().ok())
+}
+
+/// 从目录项名称中解析前缀后的编号，如 `cpu12` -> 12
+pub fn index_suffix(name: &str, prefix: &str) -> Option<usize> {
+    name.strip_prefix(prefix)?.parse().ok()
+}
+
+/// 列出目录下形如 `<prefix><N>` 的子项，按编号排序
+pub fn indexed_entries(dir: &Path, prefix: &str) -> Vec<(usize, PathBuf)> {
+    let mut entries: Vec<(usize, PathBuf)> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let name = entry.file_name();
+                    let index = index_suffix(&name.to_string_lossy(), prefix)?;
+                    Some((index, entry.path()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort_by_key(|(index, _)| *index);
+    entries
+}
+
+/// 解析 CPU 列表格式，如 `0-3,8,10-11`
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.trim()
+        .split(',')
+        .filter(|part| !part.is_empty())
+        .flat_map(|part| match part.split_once('-') {
+            Some((start, end)) => match (start.parse::<usize>(), end.parse::<usize>()) {
+                (Ok(start), Ok(end)) => (start..=end).collect(),
+                _ => Vec::new(),
+            },
+            None => part.parse().ok().into_iter().collect(),
+        })
+        .collect()
+}
+
+/// 测试用的临时目录树，模拟 sysfs / procfs 布局，离开作用域时删除
+#[cfg(test)]
+pub struct FixtureTree {
+    pub root: PathBuf,
+}
+
+#[cfg(test)]
+impl FixtureTree {
+    pub fn new(name: &str) -> Self {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        let root = std::env::temp_dir().join(format!(
+            "system-monitor-{}-{}-{}",
+            name,
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        Self { root }
+    }
+
+    /// 写入相对路径下的文件，自动创建父目录
+    pub fn write(&self, path: &str, content: &str) -> &Self {
+        let path = self.root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+        self
+    }
+
+    /// 创建符号链接，target 原样写入（可以是 /dev/dri/card0、socket:[123] 这类悬空目标）；
+    /// 指向树内路径时传入 tree.root.join(...)
+    pub fn symlink(&self, path: &str, target: impl AsRef<Path>) -> &Self {
+        let path = self.root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::os::unix::fs::symlink(target, path).unwrap();
+        self
+    }
+}
+
+#[cfg(test)]
+impl Drop for FixtureTree {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
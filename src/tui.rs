@@ -6,12 +6,18 @@ use ratatui::{
     Frame, Terminal,
     backend::CrosstermBackend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style, Stylize},
+    style::{Color, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
 };
+use chrono::{Local, TimeZone};
 use std::io::{self, Stdout};
 
+use super::oom::OomEvent;
+
+// TUI 中显示的 OOM 事件条数
+const TUI_OOM_EVENTS: usize = 3;
+
 pub type AppTerminal = Terminal<CrosstermBackend<Stdout>>;
 
 /// 初始化终端
@@ -58,6 +64,7 @@ pub fn draw_ui(
     username: &str,
     password: &str,
     interfaces: &[super::network::NetworkInterface],
+    oom_events: &[OomEvent],
 ) {
     let area = centered_rect(60, 70, f.area());
 
@@ -80,8 +87,9 @@ pub fn draw_ui(
         .margin(2)
         .constraints([
             Constraint::Length(3), // 服务状态
-            Constraint::Length(5), // 访问地址
+            Constraint::Length(7), // 访问地址
             Constraint::Length(4), // 认证信息
+            Constraint::Length(5), // 最近 OOM 事件
             Constraint::Min(1),    // 提示
         ])
         .split(inner_area);
@@ -107,9 +115,18 @@ pub fn draw_ui(
             super::network::InterfaceType::Ethernet => "🔌",
             _ => "🌐",
         };
+        // 附带链路速率，便于确认走的是千兆 / 万兆口还是无线
+        let speed = iface
+            .link
+            .speed_mbps
+            .map(|s| format!(" ({} Mb/s)", s))
+            .unwrap_or_default();
         address_lines.push(Line::from(format!(
-            "  {} {}: http://{}:{}",
-            icon, iface.name, iface.ip, port
+            "  {} {}: {}{}",
+            icon,
+            iface.name,
+            iface.url(port),
+            speed
         )));
     }
 
@@ -126,11 +143,40 @@ pub fn draw_ui(
     ]);
     f.render_widget(auth, chunks[2]);
 
+    // 最近 OOM 事件
+    let mut oom_lines = vec![Line::from(Span::styled(
+        "最近 OOM 事件:",
+        Style::default().fg(Color::Yellow),
+    ))];
+    if oom_events.is_empty() {
+        oom_lines.push(Line::from(Span::styled(
+            "  无",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for event in oom_events.iter().take(TUI_OOM_EVENTS) {
+        let time = Local
+            .timestamp_opt(event.timestamp, 0)
+            .single()
+            .map(|t| t.format("%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        let victim = match (&event.process_name, event.pid) {
+            (Some(name), Some(pid)) => format!("{} ({})", name, pid),
+            _ => "未知进程".to_string(),
+        };
+        let scope = event.cgroup.as_deref().unwrap_or("系统");
+        oom_lines.push(Line::from(Span::styled(
+            format!("  {}  {}  {}", time, victim, scope),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    f.render_widget(Paragraph::new(oom_lines), chunks[3]);
+
     // 提示
     let tips = Paragraph::new("按 Ctrl+C或q 停止服务")
         .style(Style::default().fg(Color::DarkGray))
         .alignment(Alignment::Center);
-    f.render_widget(tips, chunks[3]);
+    f.render_widget(tips, chunks[4]);
 }
 
 /// 绘制关闭界面